@@ -7,6 +7,7 @@ use clap_complete::Shell;
 use crate::config::GitSetting;
 use crate::error::TextProviderError;
 use crate::format::Format;
+use crate::version::Bump;
 
 /// Get CLI args via `clap` while also handling when we are invoked as a cargo
 /// subcommand
@@ -43,8 +44,9 @@ pub enum Command {
         #[clap(short, long, action = clap::ArgAction::Set, default_value_t = true)]
         edit: bool,
 
-        #[clap(short, long, value_enum, value_parser, default_value_t = Format::Toml)]
-        format: Format,
+        /// The format to write the header in, defaults to `edit_format` from the configuration
+        #[clap(short, long, value_enum, value_parser)]
+        format: Option<Format>,
 
         /// Read the changelog entry text from some path or stdin (via "-")
         #[clap(long, value_parser = text_provider_parser)]
@@ -64,21 +66,149 @@ pub enum Command {
         /// # Note
         ///
         /// If "commit" is given, and the "git_commit_message" setting in the configuration is NOT
-        /// set, then the normal $GIT_EDITOR or $EDITOR will be spawned for the commit message.
+        /// set, then the editor (found like for `--edit`) will be spawned for the commit message.
         ///
         /// If "commit" is given and the "git_commit_message" setting is set, this message will be
         /// used.
+        ///
+        /// Only the new entry is committed, other staged changes are left alone. The git hooks are
+        /// run like by `git commit`.
         #[clap(long, value_enum, value_parser)]
         git: Option<GitSetting>,
+
+        /// Create the fragment for a single package of the workspace
+        ///
+        /// The fragments of a package are stored in "<fragment_dir>/<package>" and released
+        /// with `create-release --package`.
+        #[clap(long)]
+        package: Option<String>,
     },
 
     /// Verify the metadata in existing changelog fragments
-    VerifyMetadata,
+    VerifyMetadata {
+        /// The format to report problems in
+        ///
+        /// All formats but "text" are printed to STDOUT, and the command exits unsuccessfully if
+        /// there are problems.
+        #[clap(long)]
+        format: Option<VerifyFormat>,
+    },
 
     /// Use the current unreleased changelog fragments to generate the changelog for the next
     /// release
-    #[clap(subcommand)]
-    CreateRelease(VersionSpec),
+    CreateRelease {
+        /// Move the fragments into the release directory even if it exists already
+        ///
+        /// The metadata of the existing release is kept, only the title and codename are
+        /// replaced if they are passed.
+        #[clap(long, default_value_t = false)]
+        force: bool,
+
+        /// Write the new version into the Cargo.toml files of the workspace
+        ///
+        /// This also updates the version requirements of dependencies between workspace members.
+        #[clap(long, default_value_t = false)]
+        update_manifests: bool,
+
+        /// Regenerate the changelog, commit the release and tag it in git
+        ///
+        /// The repository has to be clean. The commit message, the tag name and the tag message
+        /// are configured in the `release` settings.
+        #[clap(long, default_value_t = false)]
+        commit: bool,
+
+        /// Build metadata to append to the version, e.g. "build.5"
+        #[clap(long)]
+        build_metadata: Option<String>,
+
+        /// Release a single package of the workspace, using its own fragments and version
+        #[clap(long)]
+        package: Option<String>,
+
+        /// A title for the release, stored in the release metadata
+        #[clap(long)]
+        title: Option<String>,
+
+        /// A codename for the release, stored in the release metadata
+        #[clap(long)]
+        codename: Option<String>,
+
+        #[clap(subcommand)]
+        version: VersionSpec,
+    },
+
+    /// Move the fragments of a release back to the unreleased fragments
+    RevertRelease {
+        /// The version of the release to revert
+        #[clap(id = "release_version", value_name = "VERSION")]
+        version: String,
+
+        /// Revert the release even if it is tagged in git already
+        #[clap(long, default_value_t = false)]
+        force: bool,
+
+        /// Revert a release of a single package of the workspace
+        #[clap(long)]
+        package: Option<String>,
+    },
+
+    /// Mark a release as yanked in its metadata, or unmark it with `--undo`
+    Yank {
+        /// The version of the release to yank
+        #[clap(id = "release_version", value_name = "VERSION")]
+        version: String,
+
+        /// Unmark the release as yanked
+        #[clap(long, default_value_t = false)]
+        undo: bool,
+
+        /// Yank a release of a single package of the workspace
+        #[clap(long)]
+        package: Option<String>,
+    },
+
+    /// Import an existing, hand-written changelog into fragments
+    ///
+    /// Parses a changelog in the style of <https://keepachangelog.com>: "##" headings for
+    /// versions, "###" headings for sections like "Added" or "Fixed" and list items for the
+    /// entries. Each entry becomes a fragment in the directory of its version, with the section
+    /// mapped to its type by the `import` settings. Everything that cannot be imported is
+    /// reported, and the command fails.
+    Import {
+        /// The changelog to import, e.g. ".changelogs/suffix.md" after `init` moved it there, or
+        /// the configuration of the tool to import the unreleased fragments of, e.g.
+        /// "pyproject.toml" or "towncrier.toml" for towncrier and ".changie.yaml" for changie
+        path: PathBuf,
+
+        /// What to import
+        ///
+        /// Importing from towncrier or changie also adds the header fields of their fragments to
+        /// the configuration.
+        #[clap(long, value_enum, default_value_t)]
+        from: ImportSource,
+    },
+
+    /// Create unreleased fragments from the Conventional Commits in a range of revisions
+    ///
+    /// Each commit with a subject like "feat(scope)!: description" becomes a fragment, with the
+    /// type, scope, breaking flag and hash of the commit in header fields, as configured by the
+    /// `from_commits` settings. Commits that an existing fragment refers to already, merge
+    /// commits and commits of unmapped types are skipped.
+    FromCommits {
+        /// The commits to create fragments for, e.g. "v0.1.0..HEAD"
+        #[clap(value_name = "REV_RANGE")]
+        range: String,
+    },
+
+    /// Fail if files changed since a base revision, but no unreleased fragment was added
+    ///
+    /// Compares HEAD with its merge base with the base revision. Changes can be exempted with
+    /// the `fragment_gate` settings in the configuration.
+    CheckFragment {
+        /// The revision to compare HEAD with, e.g. "origin/main"
+        #[clap(long)]
+        base: String,
+    },
 
     /// Generate the changelog file from the fragments marked for release
     Generate {
@@ -88,11 +218,29 @@ pub enum Command {
 
         #[clap(long, default_value_t = false)]
         allow_dirty: bool,
+
+        /// Do not write the changelog, but fail if it differs from the generated one
+        ///
+        /// The differences are printed as a unified diff.
+        #[clap(long, default_value_t = false)]
+        check: bool,
+
+        /// Only generate the changelog of a single package of the workspace
+        ///
+        /// By default, the changelog of every package with its own fragments is generated, next
+        /// to the Cargo.toml of the package.
+        #[clap(long)]
+        package: Option<String>,
     },
 
     Show {
         #[clap(long)]
         format: Option<ShowFormat>,
+
+        /// Show the fragments of a single package of the workspace
+        #[clap(long)]
+        package: Option<String>,
+
         #[clap(subcommand)]
         selector: Option<Selector>,
     },
@@ -109,6 +257,10 @@ pub enum Command {
         #[clap(long)]
         format: Option<HasFormat>,
 
+        /// Look at the fragments of a single package of the workspace
+        #[clap(long)]
+        package: Option<String>,
+
         #[clap(subcommand)]
         selector: Selector,
     },
@@ -140,14 +292,13 @@ pub struct KV {
 }
 
 fn kv_value_parser(s: &str) -> Result<KV, String> {
-    if s.chars().filter(|c| *c == '=').count() != 1 {
-        Err(format!("Cannot parse as key-value: '{s}'"))
-    } else {
-        let (key, value) = s.split_once('=').unwrap(); // safe because above check
-        Ok(KV {
+    // Only split at the first '=', values of maps are "key=value" pairs themselves
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok(KV {
             key: key.to_string(),
             value: value.to_string(),
-        })
+        }),
+        _ => Err(format!("Cannot parse as key-value: '{s}'")),
     }
 }
 
@@ -178,9 +329,45 @@ impl TextProvider {
 
 #[derive(Clone, Debug, Subcommand)]
 pub enum VersionSpec {
+    /// Bump the patch version of the latest release
     Patch,
+
+    /// Bump the minor version of the latest release
     Minor,
+
+    /// Bump the major version of the latest release
+    ///
+    /// For versions below 1.0.0, this bumps the minor version, as "0.x" versions signal breaking
+    /// changes with their minor version. Use "custom" for releasing "1.0.0".
     Major,
+
+    /// Infer the bump level from the unreleased fragments, using the "bump_rules" from the
+    /// configuration
+    Auto,
+
+    /// Start a pre-release of the next version, e.g. "1.2.0-rc.1"
+    PreRelease {
+        /// The level to bump the latest release by
+        #[clap(value_enum)]
+        level: Bump,
+
+        /// The pre-release identifier, e.g. "alpha" or "rc"
+        identifier: String,
+    },
+
+    /// Increment the pre-release of the latest release, e.g. "1.2.0-rc.1" to "1.2.0-rc.2"
+    NextPreRelease {
+        /// Switch to another pre-release identifier, e.g. from "beta" to "rc"
+        identifier: Option<String>,
+    },
+
+    /// Release the final version of the latest pre-release
+    ///
+    /// Like for any final version, all pre-releases of that version are folded into the directory
+    /// of the final version.
+    Promote,
+
+    /// Use an explicit version
     Custom {
         #[clap(value_parser)]
         custom: String,
@@ -193,6 +380,37 @@ pub enum ShowFormat {
     Json,
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum VerifyFormat {
+    /// Human readable diagnostics, printed to STDERR
+    #[default]
+    Text,
+
+    /// A JSON object with the checked fragments and a list of problems
+    ///
+    /// Each problem has a "path", "line", "column", "field", "rule" and "message".
+    Json,
+
+    /// A JUnit XML report with one test case per fragment
+    Junit,
+
+    /// A SARIF 2.1.0 log, e.g. for code scanning annotations on pull requests
+    Sarif,
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum ImportSource {
+    /// A changelog in the style of <https://keepachangelog.com>
+    #[default]
+    KeepAChangelog,
+
+    /// The fragment directory of towncrier, with fragments like "123.bugfix.md"
+    Towncrier,
+
+    /// The unreleased changes of changie, like ".changes/unreleased/Added-20261018-120000.yaml"
+    Changie,
+}
+
 #[derive(Clone, Debug, Subcommand, serde::Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Selector {
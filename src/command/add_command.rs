@@ -21,14 +21,31 @@ use crate::fragment::FragmentDataDesc;
 use crate::fragment::FragmentDataType;
 use crate::fragment::FragmentDataTypeDefinite;
 
-#[derive(Debug, typed_builder::TypedBuilder)]
+#[derive(typed_builder::TypedBuilder)]
 pub struct AddCommand {
+    repository: git2::Repository,
     interactive: bool,
     edit: bool,
-    format: Format,
+    format: Option<Format>,
     set: Vec<KV>,
     text: Option<TextProvider>,
     git: Option<GitSetting>,
+    package: Option<String>,
+}
+
+impl std::fmt::Debug for AddCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AddCommand")
+            .field("repository", &self.repository.workdir())
+            .field("interactive", &self.interactive)
+            .field("edit", &self.edit)
+            .field("format", &self.format)
+            .field("set", &self.set)
+            .field("text", &self.text)
+            .field("git", &self.git)
+            .field("package", &self.package)
+            .finish()
+    }
 }
 
 impl crate::command::Command for AddCommand {
@@ -37,30 +54,14 @@ impl crate::command::Command for AddCommand {
         workdir: &Path,
         config: &Configuration,
     ) -> Result<Option<std::process::ExitCode>, Error> {
-        let unreleased_dir_path = ensure_fragment_dir(workdir, config)?;
-
-        let new_file_path = {
-            let new_file_name = format!(
-                "{ts}.md",
-                ts = {
-                    // We cannot use the well-known formats here, because cargo cannot package
-                    // filenames with ":" in it, but the well-known formats contain this character.
-                    // Hence we have to use our own.
-                    let fragment_file_timestamp_format = time::macros::format_description!(
-                        "[year]-[month]-[day]T[hour]_[minute]_[second]_[subsecond]"
-                    );
-                    time::OffsetDateTime::now_utc().format(&fragment_file_timestamp_format)?
-                },
-            );
-            unreleased_dir_path.join(new_file_name)
-        };
+        if let Some(package) = self.package.as_deref() {
+            crate::command::common::find_workspace_member(workdir, package)?;
+        }
+        let unreleased_dir_path = ensure_fragment_dir(workdir, config, self.package.as_deref())?;
 
-        let mut file = std::fs::OpenOptions::new()
-            .create(true)
-            .truncate(true)
-            .write(true)
-            .append(false)
-            .open(&new_file_path)?;
+        let format = self.format.unwrap_or_else(|| config.edit_format().into());
+
+        let timestamp = crate::file_name::timestamp()?;
 
         let mut fragment = crate::fragment::Fragment::empty();
 
@@ -79,7 +80,7 @@ impl crate::command::Command for AddCommand {
                     .iter()
                     .find(|kv| kv.key() == key)
                     .map(KV::value)
-                    .map(|val| FragmentData::parse(val))
+                    .map(|val| FragmentData::parse_as(val, data_desc.fragment_type()))
                 {
                     Some(Ok(val)) => Some(val),
                     Some(Err(e)) => return Some(Err(e)),
@@ -179,62 +180,59 @@ impl crate::command::Command for AddCommand {
                 }
             })
             .collect::<Result<BTreeMap<String, FragmentData>, _>>()
-            .map_err(|e| Error::Fragment(e, new_file_path.to_path_buf()))?;
+            .map_err(|e| Error::Fragment(e, unreleased_dir_path.to_path_buf()))?;
+
+        for (key, value) in fragment.header() {
+            if let Some(data_desc) = config.header_fields().get(key) {
+                data_desc.check_constraints(value).map_err(|reason| {
+                    Error::Fragment(
+                        FragmentError::ConstraintViolation {
+                            field_name: key.to_string(),
+                            reason,
+                        },
+                        unreleased_dir_path.to_path_buf(),
+                    )
+                })?;
+            }
+        }
+
+        fragment.generate_id();
+
+        let new_file_path = crate::file_name::new_fragment_path(
+            &unreleased_dir_path,
+            &fragment,
+            &timestamp,
+            format,
+            config,
+        );
+
+        // Only create the file once the header is complete, to not leave an empty fragment behind
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .append(false)
+            .open(&new_file_path)?;
 
         fragment
-            .write_to(&mut file, self.format)
+            .write_to(&mut file, format)
             .map_err(|e| Error::Fragment(e, new_file_path.to_path_buf()))?;
         file.sync_all()?;
         drop(file);
 
         if self.edit {
-            let mut editor_command = get_editor_command()?;
-            let std::process::Output { status, .. } = editor_command
-                .arg(&new_file_path)
-                .stderr(std::process::Stdio::inherit())
-                .stdout(std::process::Stdio::inherit())
-                .output()?;
-
-            if status.success() {
-                tracing::info!("Successfully edited");
-            } else {
-                tracing::error!("Failure editing {}", new_file_path.display());
-            }
+            edit_fragment(workdir, &new_file_path, self.interactive, config)?;
         }
 
         match self.git.as_ref().or_else(|| config.git().as_ref()) {
             Some(GitSetting::Add) => {
-                // We use the simple approach here and use std::command::Command for calling git
-                Command::new("git")
-                    .arg("add")
-                    .arg(&new_file_path)
-                    .stderr(std::process::Stdio::inherit())
-                    .stdout(std::process::Stdio::inherit())
-                    .output()?;
+                super::add_git::stage(&self.repository, &new_file_path)?;
             }
             Some(GitSetting::Commit) => {
-                Command::new("git")
-                    .arg("add")
-                    .arg(&new_file_path)
-                    .stderr(std::process::Stdio::inherit())
-                    .stdout(std::process::Stdio::inherit())
-                    .output()?;
-
-                let mut commit_cmd = Command::new("git");
-                commit_cmd.arg("commit").arg(&new_file_path);
-
-                if let Some(message) = config.git_commit_message().as_ref() {
-                    commit_cmd.arg("--message").arg(message);
-                }
-
-                if config.git_commit_signoff() {
-                    commit_cmd.arg("--signoff");
-                }
-
-                commit_cmd
-                    .stderr(std::process::Stdio::inherit())
-                    .stdout(std::process::Stdio::inherit())
-                    .output()?;
+                super::add_git::stage(&self.repository, &new_file_path)?;
+                let oid =
+                    super::add_git::commit(&self.repository, workdir, &new_file_path, config)?;
+                println!("Committed {} as {oid}", new_file_path.display());
             }
             None => {}
         }
@@ -243,30 +241,125 @@ impl crate::command::Command for AddCommand {
     }
 }
 
-fn ensure_fragment_dir(workdir: &Path, config: &Configuration) -> Result<PathBuf, Error> {
+fn ensure_fragment_dir(
+    workdir: &Path,
+    config: &Configuration,
+    package: Option<&str>,
+) -> Result<PathBuf, Error> {
     let unreleased_dir_path = workdir
-        .join(config.fragment_dir())
+        .join(config.fragment_dir_for(package))
         .join(crate::consts::UNRELEASED_DIR_NAME);
     std::fs::create_dir_all(&unreleased_dir_path)?;
     Ok(unreleased_dir_path)
 }
 
-fn get_editor_command() -> Result<Command, Error> {
-    let editor = match std::env::var("EDITOR") {
-        Ok(editor) => editor,
-        Err(std::env::VarError::NotPresent) => match std::env::var("VISUAL") {
-            Ok(editor) => editor,
-            Err(std::env::VarError::NotPresent) => return Err(Error::EditorEnvNotSet),
-            Err(std::env::VarError::NotUnicode(_)) => {
-                return Err(Error::EnvNotUnicode("VISUAL".to_string()))
+/// Open the fragment at `path` in the editor until it is valid, the user keeps it anyways or aborts
+///
+/// Like `git commit`, the fragment is removed if the editor fails or the user aborts. If not
+/// `interactive`, an invalid fragment is removed right away.
+fn edit_fragment(
+    workdir: &Path,
+    path: &Path,
+    interactive: bool,
+    config: &Configuration,
+) -> Result<(), Error> {
+    loop {
+        let status = get_editor_command(workdir, config)?.arg(path).status()?;
+        if !status.success() {
+            std::fs::remove_file(path)?;
+            return Err(Error::EditorFailed {
+                path: path.to_path_buf(),
+                status,
+            });
+        }
+        tracing::info!("Successfully edited");
+
+        let error = match super::verify_metadata_command::verify_entry(path, config) {
+            Ok(()) => return Ok(()),
+            Err(error) => error,
+        };
+
+        if !interactive {
+            std::fs::remove_file(path)?;
+            return Err(Error::FragmentInvalid {
+                path: path.to_path_buf(),
+                errors: vec![error],
+            });
+        }
+
+        eprintln!("{:?}", miette::Report::new(error));
+        let choice = Select::new()
+            .with_prompt("The fragment is invalid")
+            .items([
+                "Edit again",
+                "Abort and remove the fragment",
+                "Keep it anyways",
+            ])
+            .default(0)
+            .interact_opt()
+            .map_err(|e| Error::Fragment(InteractiveError::from(e).into(), path.to_path_buf()))?;
+
+        match choice {
+            Some(0) => continue,
+            Some(2) => {
+                tracing::warn!("Keeping invalid fragment {}", path.display());
+                return Ok(());
+            }
+            _ => {
+                std::fs::remove_file(path)?;
+                return Err(Error::FragmentAborted(path.to_path_buf()));
             }
-        },
-        Err(std::env::VarError::NotUnicode(_)) => {
-            return Err(Error::EnvNotUnicode("EDITOR".to_string()))
         }
+    }
+}
+
+/// Find the editor the way git does
+///
+/// The editor is the first one set of GIT_EDITOR, `editor` from the configuration, `core.editor`
+/// from the git configuration, VISUAL and EDITOR. It can have arguments, e.g. "code --wait".
+pub(super) fn get_editor_command(workdir: &Path, config: &Configuration) -> Result<Command, Error> {
+    let editor = match non_empty_env_var("GIT_EDITOR")? {
+        Some(editor) => editor,
+        None => match config.editor().clone().filter(|e| !e.trim().is_empty()) {
+            Some(editor) => editor,
+            None => match git_core_editor(workdir) {
+                Some(editor) => editor,
+                None => match non_empty_env_var("VISUAL")? {
+                    Some(editor) => editor,
+                    None => non_empty_env_var("EDITOR")?.ok_or(Error::EditorNotSet)?,
+                },
+            },
+        },
     };
 
-    Ok(Command::new(editor))
+    let mut args = comma::parse_command(&editor)
+        .filter(|args| !args.is_empty())
+        .ok_or_else(|| Error::InvalidEditorCommand(editor.clone()))?;
+    let mut command = Command::new(args.remove(0));
+    command.args(args);
+    Ok(command)
+}
+
+fn non_empty_env_var(name: &str) -> Result<Option<String>, Error> {
+    match std::env::var(name) {
+        Ok(value) if value.trim().is_empty() => Ok(None),
+        Ok(value) => Ok(Some(value)),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(std::env::VarError::NotUnicode(_)) => Err(Error::EnvNotUnicode(name.to_string())),
+    }
+}
+
+/// `core.editor` from the configuration of the repository at `workdir`, or the global one
+fn git_core_editor(workdir: &Path) -> Option<String> {
+    let git_config = git2::Repository::open(workdir)
+        .and_then(|repository| repository.config())
+        .or_else(|_| git2::Config::open_default())
+        .ok()?;
+
+    git_config
+        .get_string("core.editor")
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
 }
 
 /// Ask interactively whether these values are okay or should be changed
@@ -344,7 +437,9 @@ fn interactive_provide(
             Ok(Some((key.to_string(), FragmentData::Bool(value))))
         }
         FragmentDataType::Ty(FragmentDataTypeDefinite::Int) => {
-            let dialoguer = Input::<u64>::new().with_prompt(format!("Enter a number for '{key}'"));
+            let dialoguer = Input::<u64>::new()
+                .with_prompt(format!("Enter a number for '{key}'"))
+                .validate_with(|input: &u64| desc.check_constraints(&FragmentData::Int(*input)));
 
             let dialoguer = if let Some(data) = desc.default_value() {
                 if let FragmentData::Int(i) = data {
@@ -363,7 +458,11 @@ fn interactive_provide(
             Ok(Some((key.to_string(), FragmentData::Int(value))))
         }
         FragmentDataType::Ty(FragmentDataTypeDefinite::Str) => {
-            let dialoguer = Input::<String>::new().with_prompt(format!("Enter a text for '{key}'"));
+            let dialoguer = Input::<String>::new()
+                .with_prompt(format!("Enter a text for '{key}'"))
+                .validate_with(|input: &String| {
+                    desc.check_constraints(&FragmentData::Str(input.to_string()))
+                });
 
             let dialoguer = if let Some(data) = desc.default_value() {
                 if let FragmentData::Str(s) = data {
@@ -381,6 +480,83 @@ fn interactive_provide(
             let value = dialoguer.interact_text().map_err(InteractiveError::from)?;
             Ok(Some((key.to_string(), FragmentData::Str(value))))
         }
+        FragmentDataType::Ty(
+            ty @ (FragmentDataTypeDefinite::Date
+            | FragmentDataTypeDefinite::Url
+            | FragmentDataTypeDefinite::Semver),
+        ) => {
+            let dialoguer = Input::<String>::new()
+                .with_prompt(format!("Enter a {ty} for '{key}'"))
+                .allow_empty(!desc.required())
+                .validate_with(|input: &String| {
+                    if input.is_empty() {
+                        return Ok(());
+                    }
+                    ty.validate_str(input)?;
+                    desc.check_constraints(&FragmentData::Str(input.to_string()))
+                });
+
+            let default_value = match desc.default_value() {
+                Some(FragmentData::Str(s)) => Some(s.to_string()),
+                Some(other) => {
+                    return Err(InteractiveError::TypeError(
+                        desc.fragment_type().clone(),
+                        other.clone(),
+                    ))
+                }
+                None if *ty == FragmentDataTypeDefinite::Date => {
+                    crate::release_metadata::today().ok()
+                }
+                None => None,
+            };
+
+            let dialoguer = match default_value {
+                Some(default_value) => dialoguer.default(default_value),
+                None => dialoguer,
+            };
+
+            let value = dialoguer.interact_text().map_err(InteractiveError::from)?;
+            if value.is_empty() {
+                return Ok(None);
+            }
+            Ok(Some((key.to_string(), FragmentData::Str(value))))
+        }
+        FragmentDataType::Ty(
+            list_ty @ (FragmentDataTypeDefinite::List(_) | FragmentDataTypeDefinite::Map(_)),
+        ) => {
+            let prompt = match list_ty {
+                FragmentDataTypeDefinite::Map(_) => {
+                    format!("Enter comma seperated 'key=value' pairs for '{key}'")
+                }
+                _ => format!("Enter a comma seperated list for '{key}'"),
+            };
+            let dialoguer = Input::<String>::new()
+                .with_prompt(prompt)
+                .allow_empty(!desc.required())
+                .validate_with(|input: &String| {
+                    let value = FragmentData::parse_as(input, desc.fragment_type())
+                        .map_err(|e| e.to_string())?;
+                    desc.check_constraints(&value)
+                });
+
+            let dialoguer = if let Some(data) = desc.default_value() {
+                if desc.fragment_type().matches(data) {
+                    dialoguer.default(data.display().to_string())
+                } else {
+                    return Err(InteractiveError::TypeError(
+                        desc.fragment_type().clone(),
+                        data.clone(),
+                    ));
+                }
+            } else {
+                dialoguer
+            };
+
+            let value = dialoguer.interact_text().map_err(InteractiveError::from)?;
+            let value = FragmentData::parse_as(&value, desc.fragment_type())
+                .map_err(|e| InteractiveError::Parse(Box::new(e)))?;
+            Ok(Some((key.to_string(), value)))
+        }
         FragmentDataType::OneOf(possible_values) => {
             let dialoguer = Select::new()
                 .items(possible_values)
@@ -458,7 +634,7 @@ fn crawl_with_crawler(
             .trim()
             .to_string();
         tracing::info!("crawled = '{}'", out);
-        let data = FragmentData::parse(&out)?;
+        let data = FragmentData::parse_as(&out, expected_type)?;
         if expected_type.matches(&data) {
             Ok(data)
         } else {
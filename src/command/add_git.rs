@@ -0,0 +1,220 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::Configuration;
+use crate::error::Error;
+
+/// The name of the file in the git directory that the commit message is written to
+const COMMIT_MESSAGE_FILE_NAME: &str = "COMMIT_EDITMSG";
+
+/// Stage the file at `path`, like `git add <path>`
+pub(super) fn stage(repository: &git2::Repository, path: &Path) -> Result<(), Error> {
+    let relative = relative_path(repository, path)?;
+    repository
+        .index()
+        .and_then(|mut index| {
+            index.add_path(&relative)?;
+            index.write()
+        })
+        .map_err(|source| Error::GitStage {
+            path: relative,
+            source,
+        })
+}
+
+/// Commit only the staged file at `path`, like `git commit <path>`
+///
+/// The message is `git_commit_message` from the configuration, or else written in the editor.
+/// Like git, this runs the pre-commit, prepare-commit-msg, commit-msg and post-commit hooks, and
+/// adds a "Signed-off-by" trailer with `git_commit_signoff`.
+pub(super) fn commit(
+    repository: &git2::Repository,
+    workdir: &Path,
+    path: &Path,
+    config: &Configuration,
+) -> Result<git2::Oid, Error> {
+    let relative = relative_path(repository, path)?;
+    run_hook(repository, workdir, "pre-commit", &[])?;
+
+    let signature = repository.signature().map_err(Error::GitCommit)?;
+    let message_path = repository.path().join(COMMIT_MESSAGE_FILE_NAME);
+    let mut message = config.git_commit_message().clone().unwrap_or_default();
+    if config.git_commit_signoff() {
+        message = add_signoff(&message, &signature);
+    }
+    let use_editor = config.git_commit_message().is_none();
+    if use_editor {
+        message.push_str(&format!(
+            "\n# Please enter the commit message for the new changelog fragment. Lines\n\
+             # starting with '#' will be ignored, and an empty message aborts the commit.\n\
+             #\n\
+             # Changes to be committed:\n\
+             #\tnew file:   {}\n",
+            relative.display()
+        ));
+    }
+    std::fs::write(&message_path, &message)?;
+
+    let source = if use_editor { None } else { Some("message") };
+    let mut prepare_args = vec![message_path.as_os_str()];
+    prepare_args.extend(source.map(OsStr::new));
+    run_hook(repository, workdir, "prepare-commit-msg", &prepare_args)?;
+
+    if use_editor {
+        let status = super::add_command::get_editor_command(workdir, config)?
+            .arg(&message_path)
+            .status()?;
+        if !status.success() {
+            return Err(Error::CommitEditorFailed(status));
+        }
+    }
+    run_hook(
+        repository,
+        workdir,
+        "commit-msg",
+        &[message_path.as_os_str()],
+    )?;
+
+    let message = git2::message_prettify(
+        std::fs::read_to_string(&message_path)?,
+        use_editor.then_some(b'#'),
+    )?;
+    let is_empty = message
+        .lines()
+        .all(|line| line.trim().is_empty() || line.starts_with("Signed-off-by:"));
+    if is_empty {
+        return Err(Error::CommitMessageEmpty);
+    }
+
+    let oid = commit_path(repository, &relative, &signature, &message).map_err(Error::GitCommit)?;
+    if let Err(e) = run_hook(repository, workdir, "post-commit", &[]) {
+        tracing::warn!("Ignoring failed post-commit hook: {e}");
+    }
+    Ok(oid)
+}
+
+/// Commit the staged version of `relative` on top of HEAD, ignoring all other staged changes
+fn commit_path(
+    repository: &git2::Repository,
+    relative: &Path,
+    signature: &git2::Signature<'_>,
+    message: &str,
+) -> Result<git2::Oid, git2::Error> {
+    let entry = repository
+        .index()?
+        .get_path(relative, 0)
+        .ok_or_else(|| git2::Error::from_str(&format!("'{}' is not staged", relative.display())))?;
+
+    let parent = match repository.head() {
+        Ok(head) => Some(head.peel_to_commit()?),
+        Err(e)
+            if e.code() == git2::ErrorCode::UnbornBranch
+                || e.code() == git2::ErrorCode::NotFound =>
+        {
+            None
+        }
+        Err(e) => return Err(e),
+    };
+
+    let mut index = git2::Index::new()?;
+    if let Some(parent) = parent.as_ref() {
+        index.read_tree(&parent.tree()?)?;
+    }
+    index.add(&entry)?;
+    let tree = repository.find_tree(index.write_tree_to(repository)?)?;
+
+    repository.commit(
+        Some("HEAD"),
+        signature,
+        signature,
+        message,
+        &tree,
+        &parent.iter().collect::<Vec<_>>(),
+    )
+}
+
+/// Append a "Signed-off-by" trailer for `signature` to `message`, unless it has one already
+fn add_signoff(message: &str, signature: &git2::Signature<'_>) -> String {
+    let trailer = format!(
+        "Signed-off-by: {} <{}>",
+        String::from_utf8_lossy(signature.name_bytes()),
+        String::from_utf8_lossy(signature.email_bytes())
+    );
+    if message.lines().any(|line| line.trim_end() == trailer) {
+        return message.to_string();
+    }
+    format!("{}\n\n{trailer}\n", message.trim_end())
+}
+
+/// Run the git hook `name` with `args`, if it exists and is executable
+fn run_hook(
+    repository: &git2::Repository,
+    workdir: &Path,
+    name: &str,
+    args: &[&OsStr],
+) -> Result<(), Error> {
+    let hooks_dir = repository
+        .config()
+        .and_then(|config| config.get_path("core.hooksPath"))
+        .map(|hooks_path| workdir.join(hooks_path))
+        .unwrap_or_else(|_| repository.path().join("hooks"));
+    let hook = hooks_dir.join(name);
+    if !is_executable(&hook) {
+        return Ok(());
+    }
+
+    tracing::debug!("Running git hook {}", hook.display());
+    let status = Command::new(&hook)
+        .args(args)
+        .current_dir(workdir)
+        .status()?;
+    if !status.success() {
+        return Err(Error::GitHookFailed {
+            hook: name.to_string(),
+            status,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// `path` relative to the worktree of `repository`, as git2 expects it
+fn relative_path(repository: &git2::Repository, path: &Path) -> Result<PathBuf, Error> {
+    let workdir = repository.workdir().ok_or(Error::NoWorkTree)?;
+    path.strip_prefix(workdir)
+        .map(Path::to_path_buf)
+        .map_err(|_| Error::NotInWorkTree(path.to_path_buf()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_signoff() {
+        let signature = git2::Signature::now("Jane Doe", "jane@example.com").unwrap();
+        let trailer = "Signed-off-by: Jane Doe <jane@example.com>";
+
+        assert_eq!(
+            add_signoff("Add entry", &signature),
+            format!("Add entry\n\n{trailer}\n")
+        );
+        assert_eq!(add_signoff("", &signature), format!("\n\n{trailer}\n"));
+
+        let signed = format!("Add entry\n\n{trailer}\n");
+        assert_eq!(add_signoff(&signed, &signature), signed);
+    }
+}
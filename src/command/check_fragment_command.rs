@@ -0,0 +1,168 @@
+use std::path::Path;
+
+use crate::{config::Configuration, error::Error};
+
+#[derive(typed_builder::TypedBuilder)]
+pub struct CheckFragmentCommand {
+    repository: git2::Repository,
+    base: String,
+}
+
+impl std::fmt::Debug for CheckFragmentCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CheckFragmentCommand")
+            .field("repository", &self.repository.workdir())
+            .field("base", &self.base)
+            .finish()
+    }
+}
+
+impl crate::command::Command for CheckFragmentCommand {
+    fn execute(
+        self,
+        workdir: &Path,
+        config: &Configuration,
+    ) -> Result<Option<std::process::ExitCode>, Error> {
+        let gate = config.fragment_gate();
+
+        if let Some(skip_file) = gate.skip_file() {
+            if workdir.join(skip_file).exists() {
+                println!(
+                    "Not checking for a fragment, {} exists",
+                    skip_file.display()
+                );
+                return Ok(None);
+            }
+        }
+
+        let base = self
+            .repository
+            .revparse_single(&self.base)?
+            .peel_to_commit()?;
+        let head = self.repository.head()?.peel_to_commit()?;
+
+        if let Some(skip_trailer) = gate.skip_trailer() {
+            if let Some(commit) =
+                find_commit_with_trailer(&self.repository, &base, &head, skip_trailer)?
+            {
+                println!("Not checking for a fragment, {commit} has '{skip_trailer}'");
+                return Ok(None);
+            }
+        }
+
+        let merge_base = self.repository.merge_base(base.id(), head.id())?;
+        let merge_base_tree = self.repository.find_commit(merge_base)?.tree()?;
+        let diff =
+            self.repository
+                .diff_tree_to_tree(Some(&merge_base_tree), Some(&head.tree()?), None)?;
+
+        let exempt = build_globset(gate.exempt())?;
+        let fragment_dir = config.fragment_dir();
+
+        let mut fragments = Vec::new();
+        let mut changed = Vec::new();
+        for delta in diff.deltas() {
+            let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) else {
+                continue;
+            };
+
+            if let Ok(fragment_path) = path.strip_prefix(fragment_dir) {
+                if delta.status() == git2::Delta::Added && is_unreleased_fragment(fragment_path) {
+                    fragments.push(path.to_path_buf());
+                }
+                continue;
+            }
+
+            if exempt.is_match(path) {
+                tracing::debug!("Exempt from requiring a fragment: {}", path.display());
+                continue;
+            }
+
+            changed.push(path.to_path_buf());
+        }
+
+        if changed.is_empty() {
+            println!("No changes that require a fragment since {}", self.base);
+            return Ok(None);
+        }
+
+        if fragments.is_empty() {
+            return Err(Error::FragmentMissing {
+                base: self.base,
+                files: changed,
+            });
+        }
+
+        for fragment in fragments {
+            println!("Found fragment: {}", fragment.display());
+        }
+        Ok(None)
+    }
+}
+
+/// Whether `path`, relative to the fragment directory, is an unreleased fragment
+///
+/// Unreleased fragments are in the unreleased directory, either of the fragment directory or of
+/// a package.
+fn is_unreleased_fragment(path: &Path) -> bool {
+    if path.file_name().is_none_or(|name| name == ".gitkeep") {
+        return false;
+    }
+
+    let Some(dir) = path.parent() else {
+        return false;
+    };
+
+    let unreleased = Path::new(crate::consts::UNRELEASED_DIR_NAME);
+    dir == unreleased || dir.components().count() == 2 && dir.ends_with(unreleased)
+}
+
+/// Find the first commit that is in `head` but not in `base` and has the `trailer` in its message
+fn find_commit_with_trailer(
+    repository: &git2::Repository,
+    base: &git2::Commit<'_>,
+    head: &git2::Commit<'_>,
+    trailer: &str,
+) -> Result<Option<git2::Oid>, Error> {
+    let mut revwalk = repository.revwalk()?;
+    revwalk.push(head.id())?;
+    revwalk.hide(base.id())?;
+
+    for oid in revwalk {
+        let oid = oid?;
+        let commit = repository.find_commit(oid)?;
+        let has_trailer = commit
+            .message()
+            .is_ok_and(|message| message.lines().any(|line| line.trim() == trailer.trim()));
+
+        if has_trailer {
+            return Ok(Some(oid));
+        }
+    }
+
+    Ok(None)
+}
+
+fn build_globset(patterns: &[String]) -> Result<globset::GlobSet, Error> {
+    let mut builder = globset::GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(globset::Glob::new(pattern)?);
+    }
+    Ok(builder.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_unreleased_fragment() {
+        assert!(is_unreleased_fragment(Path::new("unreleased/foo.md")));
+        assert!(is_unreleased_fragment(Path::new(
+            "some-pkg/unreleased/foo.md"
+        )));
+        assert!(!is_unreleased_fragment(Path::new("unreleased/.gitkeep")));
+        assert!(!is_unreleased_fragment(Path::new("0.1.0/foo.md")));
+        assert!(!is_unreleased_fragment(Path::new("template.md")));
+    }
+}
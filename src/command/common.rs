@@ -1,8 +1,11 @@
-use std::path::Path;
+use std::{io::BufReader, path::Path};
 
 use crate::{
     cli::VersionSpec,
+    config::Configuration,
     error::{Error, VersionError},
+    fragment::Fragment,
+    version::{bump_version, next_pre_release, promote_pre_release, start_pre_release, Bump},
 };
 
 pub fn get_version_from_path(path: &Path) -> Result<Option<semver::Version>, VersionError> {
@@ -29,34 +32,247 @@ pub fn get_version_from_path(path: &Path) -> Result<Option<semver::Version>, Ver
         .transpose()
 }
 
-pub fn find_version_string(workdir: &Path, version: &VersionSpec) -> Result<String, Error> {
-    use cargo_metadata::MetadataCommand;
+/// Get the pre-release a fragment was released with, if it was folded into its final release
+///
+/// Folded fragments live in `<version>/<pre-release>/`, so this is the second version in `path`.
+pub fn get_folded_pre_release_from_path(
+    path: &Path,
+) -> Result<Option<semver::Version>, VersionError> {
+    let mut versions = path.components().filter_map(|comp| match comp {
+        std::path::Component::Normal(comp) => match comp.to_str() {
+            None => Some(Err(VersionError::Utf8(path.to_path_buf()))),
+            Some(s) => semver::Version::parse(s).ok().map(Ok),
+        },
+        _ => None,
+    });
 
-    if let VersionSpec::Custom { custom } = version {
-        Ok(custom.clone())
-    } else {
-        let metadata = MetadataCommand::new()
-            .manifest_path(workdir.join("./Cargo.toml"))
-            .exec()?;
+    versions.next().transpose()?;
+    versions.next().transpose()
+}
 
-        let workspace_member_ids = &metadata.workspace_members;
+/// Find the version string for the release described by `version`
+///
+/// For all but the custom variant, the version is computed from the latest released version in
+/// the fragment directory, or from the version in Cargo.toml if nothing was released yet.
+///
+/// If `package` is given, the fragment directory and Cargo.toml of that package are used.
+pub fn find_version_string(
+    workdir: &Path,
+    config: &Configuration,
+    package: Option<&str>,
+    version: &VersionSpec,
+) -> Result<String, Error> {
+    let current = || find_current_version(workdir, config, package);
 
-        let versions = metadata
-            .packages
-            .iter()
-            .filter(|pkg| workspace_member_ids.contains(&pkg.id))
-            .map(|pkg| &pkg.version)
-            .collect::<Vec<_>>();
+    let version = match version {
+        VersionSpec::Custom { custom } => return Ok(custom.clone()),
+        VersionSpec::Patch => bump_version(&current()?, Bump::Patch),
+        VersionSpec::Minor => bump_version(&current()?, Bump::Minor),
+        VersionSpec::Major => bump_version(&current()?, Bump::Major),
+        VersionSpec::Auto => {
+            let bump = infer_bump(workdir, config, package)?;
+            bump_version(&current()?, bump)
+        }
+        VersionSpec::PreRelease { level, identifier } => {
+            start_pre_release(&current()?, *level, identifier)?
+        }
+        VersionSpec::NextPreRelease { identifier } => {
+            next_pre_release(&current()?, identifier.as_deref())?
+        }
+        VersionSpec::Promote => promote_pre_release(&current()?)?,
+    };
+
+    Ok(version.to_string())
+}
+
+/// Find the latest released version, or the version from Cargo.toml if nothing was released yet
+fn find_current_version(
+    workdir: &Path,
+    config: &Configuration,
+    package: Option<&str>,
+) -> Result<semver::Version, Error> {
+    if let Some(version) = find_latest_released_version(workdir, config, package)? {
+        tracing::debug!("Latest released version: {version}");
+        return Ok(version);
+    }
+
+    let version = match package {
+        None => find_cargo_version(workdir)?,
+        Some(package) => find_workspace_member(workdir, package)?.version,
+    };
+    tracing::debug!("Nothing released yet, using version from Cargo.toml: {version}");
+    Ok(version)
+}
+
+/// Find the highest version for which there is a release directory in the fragment directory
+pub fn find_latest_released_version(
+    workdir: &Path,
+    config: &Configuration,
+    package: Option<&str>,
+) -> Result<Option<semver::Version>, Error> {
+    let fragment_dir = workdir.join(config.fragment_dir_for(package));
+    if !fragment_dir.exists() {
+        return Ok(None);
+    }
 
-        if versions.is_empty() {
-            return Err(Error::NoVersionInCargoToml);
+    let mut latest: Option<semver::Version> = None;
+    for entry in std::fs::read_dir(fragment_dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
         }
 
-        let first = versions[0];
-        let all_versions_same = versions.iter().all(|v| *v == first);
-        if !all_versions_same {
-            return Err(Error::WorkspaceVersionsNotEqual);
+        let Some(version) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| semver::Version::parse(name).ok())
+        else {
+            continue;
+        };
+
+        if latest.as_ref().is_none_or(|latest| version > *latest) {
+            latest = Some(version);
         }
-        Ok(first.to_string())
     }
+
+    Ok(latest)
+}
+
+/// Find the members of the workspace at `workdir`
+pub fn find_workspace_members(workdir: &Path) -> Result<Vec<cargo_metadata::Package>, Error> {
+    let metadata = cargo_metadata::MetadataCommand::new()
+        .manifest_path(workdir.join("./Cargo.toml"))
+        .no_deps()
+        .exec()?;
+
+    Ok(metadata
+        .packages
+        .into_iter()
+        .filter(|pkg| metadata.workspace_members.contains(&pkg.id))
+        .collect())
+}
+
+/// Find the workspace member named `package` in the workspace at `workdir`
+pub fn find_workspace_member(
+    workdir: &Path,
+    package: &str,
+) -> Result<cargo_metadata::Package, Error> {
+    find_workspace_members(workdir)?
+        .into_iter()
+        .find(|pkg| pkg.name.as_str() == package)
+        .ok_or_else(|| Error::UnknownPackage(package.to_string()))
+}
+
+/// Find the names of the packages that have their own fragment directory
+///
+/// These are all directories in the fragment directory that are neither the directory for
+/// unreleased fragments nor a release directory.
+pub fn find_packages_with_fragments(
+    workdir: &Path,
+    config: &Configuration,
+) -> Result<Vec<String>, Error> {
+    let fragment_dir = workdir.join(config.fragment_dir());
+    if !fragment_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut packages = Vec::new();
+    for entry in std::fs::read_dir(fragment_dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+
+        let Some(name) = entry.file_name().to_str().map(ToString::to_string) else {
+            continue;
+        };
+
+        if name != crate::consts::UNRELEASED_DIR_NAME && semver::Version::parse(&name).is_err() {
+            packages.push(name);
+        }
+    }
+
+    packages.sort();
+    Ok(packages)
+}
+
+/// Find the version of the workspace members from Cargo.toml
+///
+/// Fails if the workspace members do not all have the same version.
+pub fn find_cargo_version(workdir: &Path) -> Result<semver::Version, Error> {
+    use cargo_metadata::MetadataCommand;
+
+    let metadata = MetadataCommand::new()
+        .manifest_path(workdir.join("./Cargo.toml"))
+        .exec()?;
+
+    let workspace_member_ids = &metadata.workspace_members;
+
+    let versions = metadata
+        .packages
+        .iter()
+        .filter(|pkg| workspace_member_ids.contains(&pkg.id))
+        .map(|pkg| &pkg.version)
+        .collect::<Vec<_>>();
+
+    if versions.is_empty() {
+        return Err(Error::NoVersionInCargoToml);
+    }
+
+    let first = versions[0];
+    let all_versions_same = versions.iter().all(|v| *v == first);
+    if !all_versions_same {
+        return Err(Error::WorkspaceVersionsNotEqual);
+    }
+    Ok(first.clone())
+}
+
+/// Infer the bump level from the unreleased fragments using the configured bump rules
+///
+/// Prints the fragments that caused the chosen bump level.
+pub fn infer_bump(
+    workdir: &Path,
+    config: &Configuration,
+    package: Option<&str>,
+) -> Result<Bump, Error> {
+    let pathes = crate::selector::SelectorExecutor::new(None)
+        .package(package)
+        .run(workdir, config)?;
+
+    let mut matches = Vec::new();
+    for path in pathes {
+        let fragment = std::fs::OpenOptions::new()
+            .read(true)
+            .create(false)
+            .write(false)
+            .open(&path)
+            .map_err(crate::error::FragmentError::from)
+            .map(BufReader::new)
+            .and_then(|mut reader| Fragment::from_file_reader(&path, &mut reader))
+            .map_err(|e| Error::Fragment(e, path.to_path_buf()))?;
+
+        for rule in config.bump_rules() {
+            if fragment.header().get(rule.header()) == Some(rule.value()) {
+                let path = path.strip_prefix(workdir).unwrap_or(&path).to_path_buf();
+                matches.push((rule.bump(), path, rule));
+            }
+        }
+    }
+
+    let Some(bump) = matches.iter().map(|(bump, _, _)| *bump).max() else {
+        println!("No bump rule matched the unreleased fragments, creating a patch release");
+        return Ok(Bump::Patch);
+    };
+
+    println!("Creating a {bump} release, because of:");
+    for (_, path, rule) in matches.iter().filter(|(b, _, _)| *b == bump) {
+        println!(
+            "  {path}: {header} = {value}",
+            path = path.display(),
+            header = rule.header(),
+            value = rule.value().display()
+        );
+    }
+
+    Ok(bump)
 }
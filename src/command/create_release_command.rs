@@ -2,11 +2,36 @@ use std::path::{Path, PathBuf};
 
 use crate::{
     cli::VersionSpec, command::common::find_version_string, config::Configuration, error::Error,
+    release_metadata::ReleaseMetadata,
 };
 
-#[derive(Debug, typed_builder::TypedBuilder)]
+#[derive(typed_builder::TypedBuilder)]
 pub struct CreateReleaseCommand {
+    repository: git2::Repository,
     version: VersionSpec,
+    force: bool,
+    update_manifests: bool,
+    commit: bool,
+    build_metadata: Option<String>,
+    package: Option<String>,
+    title: Option<String>,
+    codename: Option<String>,
+}
+
+impl std::fmt::Debug for CreateReleaseCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CreateReleaseCommand")
+            .field("repository", &self.repository.workdir())
+            .field("version", &self.version)
+            .field("force", &self.force)
+            .field("update_manifests", &self.update_manifests)
+            .field("commit", &self.commit)
+            .field("build_metadata", &self.build_metadata)
+            .field("package", &self.package)
+            .field("title", &self.title)
+            .field("codename", &self.codename)
+            .finish()
+    }
 }
 
 impl crate::command::Command for CreateReleaseCommand {
@@ -15,16 +40,65 @@ impl crate::command::Command for CreateReleaseCommand {
         workdir: &Path,
         config: &Configuration,
     ) -> Result<Option<std::process::ExitCode>, Error> {
-        let version_string = find_version_string(workdir, &self.version)?;
+        if self.commit {
+            super::release_git::check_can_commit(&self.repository)?;
+        }
+
+        let package = self.package.as_deref();
+        let version_string = find_version_string(workdir, config, package, &self.version)?;
+        let version_string = match self.build_metadata.as_ref() {
+            None => version_string,
+            Some(build_metadata) => {
+                let mut version = semver::Version::parse(&version_string)?;
+                version.build = semver::BuildMetadata::new(build_metadata)?;
+                version.to_string()
+            }
+        };
+        let version = semver::Version::parse(&version_string)?;
+        let fragment_dir = workdir.join(config.fragment_dir_for(package));
+
+        let date = if config.add_version_date() {
+            Some(crate::release_metadata::today()?)
+        } else {
+            None
+        };
+        let commit = self
+            .repository
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .map(|commit| commit.id().to_string())
+            .ok();
+        let metadata = ReleaseMetadata::new(date, self.title, self.codename, commit);
+        let metadata = match ReleaseMetadata::load(&fragment_dir.join(&version_string))? {
+            Some(existing) => existing.merge(metadata),
+            None => metadata,
+        };
+
+        // Everything that can fail in committing the release is checked before moving fragments
+        let messages = if self.commit {
+            Some(super::release_git::prepare_release(
+                &self.repository,
+                workdir,
+                config,
+                &version,
+                package,
+                &metadata,
+            )?)
+        } else {
+            None
+        };
+
         tracing::debug!("Creating new directory for version '{}'", version_string);
-        let release_dir = ensure_release_dir(workdir, config, &version_string)?;
-        let unreleased_dir = workdir
-            .join(config.fragment_dir())
-            .join(crate::consts::UNRELEASED_DIR_NAME);
+        let release_dir = ensure_release_dir(&fragment_dir, &version_string, self.force)?;
+        let unreleased_dir = fragment_dir.join(crate::consts::UNRELEASED_DIR_NAME);
 
         tracing::info!("Computed unrelease dir: {}", unreleased_dir.display());
         tracing::info!("Computed release dir: {}", release_dir.display());
 
+        if !unreleased_dir.exists() {
+            std::fs::create_dir_all(&unreleased_dir)?;
+        }
+
         let to_be_moved = std::fs::read_dir(&unreleased_dir)?
             .map(|rdirentry| rdirentry.map(|de| de.path()).map_err(Error::from))
             .filter(|rpb| match rpb {
@@ -42,16 +116,86 @@ impl crate::command::Command for CreateReleaseCommand {
             std::fs::rename(entry, destination)?;
         }
 
+        if version.pre.is_empty() {
+            fold_pre_releases(&fragment_dir, &version, &release_dir)?;
+        }
+
+        metadata.write(&release_dir)?;
+
+        if self.update_manifests {
+            for manifest in crate::manifest::update_workspace_versions(workdir, &version, package)?
+            {
+                tracing::info!("Updated version in {}", manifest.display());
+            }
+        }
+
+        if let Some(messages) = messages {
+            super::release_git::commit_release(
+                &self.repository,
+                workdir,
+                config,
+                &version_string,
+                package,
+                messages,
+            )?;
+        }
+
         Ok(None)
     }
 }
 
 fn ensure_release_dir(
-    workdir: &Path,
-    config: &Configuration,
+    fragment_dir: &Path,
     version_string: &str,
+    force: bool,
 ) -> Result<PathBuf, Error> {
-    let release_dir = workdir.join(config.fragment_dir()).join(version_string);
+    let release_dir = fragment_dir.join(version_string);
+    if release_dir.exists() && !force {
+        return Err(Error::ReleaseDirExists(release_dir));
+    }
     std::fs::create_dir_all(&release_dir)?;
     Ok(release_dir)
 }
+
+/// Move the directories of all pre-releases of `version` into the `release_dir`
+///
+/// The pre-release directories are kept as subdirectories, so that the fragments count as
+/// released with `version`, while the changelog can still show which pre-release they were
+/// released with first.
+fn fold_pre_releases(
+    fragment_dir: &Path,
+    version: &semver::Version,
+    release_dir: &Path,
+) -> Result<(), Error> {
+    for entry in std::fs::read_dir(fragment_dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+
+        let Some(pre_release) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| semver::Version::parse(name).ok())
+        else {
+            continue;
+        };
+
+        let is_pre_release_of_version = !pre_release.pre.is_empty()
+            && pre_release.major == version.major
+            && pre_release.minor == version.minor
+            && pre_release.patch == version.patch;
+
+        if is_pre_release_of_version {
+            let destination = release_dir.join(entry.file_name());
+            tracing::info!(
+                "Folding pre-release: {} -> {}",
+                entry.path().display(),
+                destination.display()
+            );
+            std::fs::rename(entry.path(), destination)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,361 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::import_command::{changes_header_fields, split_subject, NewHeaderField};
+use crate::config::Configuration;
+use crate::error::Error;
+use crate::fragment::{Fragment, FragmentData, FragmentDataType, FragmentDataTypeDefinite};
+
+/// The shortest abbreviated hash that is accepted as a reference to a commit
+const MIN_HASH_LEN: usize = 7;
+
+/// Create unreleased fragments from the Conventional Commits in a range of revisions
+#[derive(typed_builder::TypedBuilder)]
+pub struct FromCommitsCommand {
+    repository: git2::Repository,
+    range: String,
+}
+
+impl std::fmt::Debug for FromCommitsCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FromCommitsCommand")
+            .field("repository", &self.repository.workdir())
+            .field("range", &self.range)
+            .finish()
+    }
+}
+
+impl crate::command::Command for FromCommitsCommand {
+    fn execute(
+        self,
+        workdir: &Path,
+        config: &Configuration,
+    ) -> Result<Option<std::process::ExitCode>, Error> {
+        let settings = config.from_commits();
+        let referenced = referenced_commits(workdir, config)?;
+
+        let unreleased_dir = workdir
+            .join(config.fragment_dir())
+            .join(crate::consts::UNRELEASED_DIR_NAME);
+        std::fs::create_dir_all(&unreleased_dir)?;
+        let format = crate::format::Format::from(config.edit_format());
+
+        let mut created = 0;
+        let mut skipped = 0;
+        for oid in walk_range(&self.repository, &self.range)? {
+            let commit = self.repository.find_commit(oid?)?;
+            let hash = commit.id().to_string();
+
+            if commit.parent_count() > 1 {
+                tracing::debug!("Skipping merge commit {hash}");
+                skipped += 1;
+                continue;
+            }
+
+            if referenced
+                .iter()
+                .any(|reference| hash.starts_with(reference.as_str()))
+            {
+                tracing::debug!("Skipping {hash}, it has a fragment already");
+                skipped += 1;
+                continue;
+            }
+
+            let Ok(message) = commit.message() else {
+                tracing::warn!("Skipping {hash}, its message is not valid UTF-8");
+                skipped += 1;
+                continue;
+            };
+            let Some(conventional) = ConventionalCommit::parse(message) else {
+                tracing::debug!("Skipping {hash}, it is not a Conventional Commit");
+                skipped += 1;
+                continue;
+            };
+            let Some(type_value) = settings
+                .types()
+                .iter()
+                .find(|(commit_type, _)| commit_type.eq_ignore_ascii_case(conventional.commit_type))
+                .map(|(_, type_value)| type_value)
+            else {
+                tracing::debug!(
+                    "Skipping {hash}, commit type '{}' is not mapped",
+                    conventional.commit_type
+                );
+                skipped += 1;
+                continue;
+            };
+
+            let mut fragment = Fragment::empty();
+            let header = fragment.header_mut();
+            header.insert(
+                settings.subject_field().clone(),
+                FragmentData::Str(conventional.description.to_string()),
+            );
+            header.insert(
+                settings.type_field().clone(),
+                FragmentData::Str(type_value.clone()),
+            );
+            if let Some(scope) = conventional.scope {
+                header.insert(
+                    settings.scope_field().clone(),
+                    FragmentData::Str(scope.to_string()),
+                );
+            }
+            if conventional.breaking {
+                header.insert(settings.breaking_field().clone(), FragmentData::Bool(true));
+            }
+            header.insert(
+                settings.commit_field().clone(),
+                FragmentData::Str(hash.clone()),
+            );
+            fragment.set_text(conventional.body);
+            fragment.generate_id();
+
+            let path = crate::file_name::new_fragment_path(
+                &unreleased_dir,
+                &fragment,
+                &crate::file_name::timestamp()?,
+                format,
+                config,
+            );
+            let mut file = std::fs::File::create(&path)?;
+            fragment
+                .write_to(&mut file, format)
+                .map_err(|e| Error::Fragment(e, path.to_path_buf()))?;
+            file.sync_all()?;
+
+            created += 1;
+            println!(
+                "Created {} for {}",
+                path.strip_prefix(workdir).unwrap_or(&path).display(),
+                &hash[..MIN_HASH_LEN]
+            );
+        }
+        println!("Created {created} fragments, skipped {skipped} commits");
+
+        if created > 0 {
+            add_missing_header_fields(workdir, config)?;
+        }
+        Ok(None)
+    }
+}
+
+/// Walk the commits in `range` from the oldest to the newest
+///
+/// The range is either a single revision (all of its history) or "from..to", like for `git log`.
+fn walk_range<'r>(
+    repository: &'r git2::Repository,
+    range: &str,
+) -> Result<git2::Revwalk<'r>, Error> {
+    let spec = repository.revparse(range)?;
+    let mut revwalk = repository.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+
+    match (spec.from(), spec.to()) {
+        (Some(from), Some(to)) if spec.mode().contains(git2::RevparseMode::MERGE_BASE) => {
+            revwalk.push(from.id())?;
+            revwalk.push(to.id())?;
+            revwalk.hide(repository.merge_base(from.id(), to.id())?)?;
+        }
+        (Some(from), Some(to)) => {
+            revwalk.hide(from.id())?;
+            revwalk.push(to.id())?;
+        }
+        (Some(rev), None) | (None, Some(rev)) => revwalk.push(rev.id())?,
+        (None, None) => {}
+    }
+    Ok(revwalk)
+}
+
+/// The commits that the `commit_field` of an existing fragment refers to
+fn referenced_commits(workdir: &Path, config: &Configuration) -> Result<HashSet<String>, Error> {
+    let commit_field = config.from_commits().commit_field();
+    let mut referenced = HashSet::new();
+
+    for entry in walkdir::WalkDir::new(workdir.join(config.fragment_dir())).follow_links(false) {
+        let entry = entry?;
+        if !super::verify_metadata_command::is_fragment_file(&entry) {
+            continue;
+        }
+
+        let fragment = std::fs::File::open(entry.path())
+            .map_err(Error::from)
+            .and_then(|mut file| {
+                Fragment::from_file_reader(entry.path(), &mut file)
+                    .map_err(|e| Error::Fragment(e, entry.path().to_path_buf()))
+            })?;
+        if let Some(FragmentData::Str(hash)) = fragment.header().get(commit_field) {
+            if hash.len() >= MIN_HASH_LEN {
+                referenced.insert(hash.to_lowercase());
+            }
+        }
+    }
+
+    Ok(referenced)
+}
+
+/// Add the header fields that the created fragments use, if they are not configured yet
+///
+/// The allowed values of the type field are extended by the mapped commit types. Added fields
+/// are optional, as the existing fragments do not have them.
+fn add_missing_header_fields(workdir: &Path, config: &Configuration) -> Result<(), Error> {
+    let settings = config.from_commits();
+    let string = || FragmentDataType::Ty(FragmentDataTypeDefinite::Str);
+    let mut type_values = Vec::<String>::new();
+    for value in settings.types().values() {
+        if !type_values.contains(value) {
+            type_values.push(value.clone());
+        }
+    }
+
+    let header_fields = [
+        (settings.subject_field(), string()),
+        (settings.type_field(), FragmentDataType::OneOf(type_values)),
+        (settings.scope_field(), string()),
+        (
+            settings.breaking_field(),
+            FragmentDataType::Ty(FragmentDataTypeDefinite::Bool),
+        ),
+        (settings.commit_field(), string()),
+    ]
+    .into_iter()
+    .map(|(name, fragment_type)| NewHeaderField {
+        name: name.clone(),
+        fragment_type,
+        required: false,
+    })
+    .filter(|field| changes_header_fields(config.header_fields(), field))
+    .collect::<Vec<_>>();
+
+    if header_fields.is_empty() {
+        return Ok(());
+    }
+
+    let config_path = crate::config::find_config_file(workdir).ok_or(Error::ConfigDoesNotExist)?;
+    super::import_command::update_header_fields(
+        &config_path,
+        config.header_fields(),
+        &header_fields,
+    )?;
+    println!(
+        "Updated header fields in {}: {}",
+        config_path.display(),
+        header_fields
+            .iter()
+            .map(|field| field.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    Ok(())
+}
+
+/// A commit message in the style of <https://www.conventionalcommits.org>
+#[derive(Debug, PartialEq, Eq)]
+struct ConventionalCommit<'a> {
+    commit_type: &'a str,
+    scope: Option<&'a str>,
+    breaking: bool,
+    description: &'a str,
+    body: String,
+}
+
+impl<'a> ConventionalCommit<'a> {
+    /// Parse a message with a subject like "feat(scope)!: description"
+    ///
+    /// A change is also breaking if the message has a "BREAKING CHANGE" footer.
+    fn parse(message: &'a str) -> Option<Self> {
+        let subject = message.lines().next()?.trim();
+        let (prefix, description) = subject.split_once(": ")?;
+        let description = description.trim();
+        let (prefix, breaking) = match prefix.strip_suffix('!') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+        let (commit_type, scope) = match prefix.strip_suffix(')') {
+            Some(prefix) => {
+                let (commit_type, scope) = prefix.split_once('(')?;
+                (commit_type, Some(scope.trim()))
+            }
+            None => (prefix, None),
+        };
+
+        let is_word = |s: &str| {
+            !s.is_empty()
+                && s.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        };
+        if !is_word(commit_type)
+            || scope.is_some_and(|scope| scope.is_empty() || scope.contains(['(', ')']))
+            || description.is_empty()
+        {
+            return None;
+        }
+
+        let body = split_subject(message)
+            .map(|(_, body)| body)
+            .unwrap_or_default();
+        let breaking = breaking
+            || body.lines().any(|line| {
+                line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+            });
+
+        Some(ConventionalCommit {
+            commit_type,
+            scope,
+            breaking,
+            description,
+            body,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_conventional_commit() {
+        assert_eq!(
+            ConventionalCommit::parse("feat(parser)!: Support comments\n\nIn all places\n"),
+            Some(ConventionalCommit {
+                commit_type: "feat",
+                scope: Some("parser"),
+                breaking: true,
+                description: "Support comments",
+                body: "In all places".to_string(),
+            })
+        );
+        assert_eq!(
+            ConventionalCommit::parse("fix: Crash on empty input"),
+            Some(ConventionalCommit {
+                commit_type: "fix",
+                scope: None,
+                breaking: false,
+                description: "Crash on empty input",
+                body: String::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_breaking_change_footer() {
+        let commit = ConventionalCommit::parse(
+            "refactor: Rename the option\n\nBREAKING CHANGE: `--foo` is now `--bar`",
+        )
+        .unwrap();
+        assert!(commit.breaking);
+    }
+
+    #[test]
+    fn test_parse_not_conventional() {
+        for message in [
+            "Fix the crash",
+            "Merge branch 'main': conflicts",
+            "feat(): Empty scope",
+            "feat(parser: Unclosed scope",
+            "feat: ",
+        ] {
+            assert_eq!(ConventionalCommit::parse(message), None, "{message}");
+        }
+    }
+}
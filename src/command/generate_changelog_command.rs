@@ -1,14 +1,18 @@
-use std::ffi::OsStr;
 use std::io::Write;
+use std::path::PathBuf;
 use std::{collections::BTreeMap, io::BufReader, path::Path};
 
-use crate::{config::Configuration, error::Error, fragment::Fragment};
+use crate::{
+    config::Configuration, error::Error, fragment::Fragment, release_metadata::ReleaseMetadata,
+};
 
 #[derive(typed_builder::TypedBuilder)]
 pub struct GenerateChangelogCommand {
     repository: git2::Repository,
     all: bool,
     allow_dirty: bool,
+    check: bool,
+    package: Option<String>,
 }
 
 impl std::fmt::Debug for GenerateChangelogCommand {
@@ -17,6 +21,8 @@ impl std::fmt::Debug for GenerateChangelogCommand {
             .field("repository", &self.repository.workdir())
             .field("all", &self.all)
             .field("allow_dirty", &self.allow_dirty)
+            .field("check", &self.check)
+            .field("package", &self.package)
             .finish_non_exhaustive()
     }
 }
@@ -27,70 +33,276 @@ impl crate::command::Command for GenerateChangelogCommand {
         workdir: &Path,
         config: &Configuration,
     ) -> Result<Option<std::process::ExitCode>, Error> {
-        if crate::util::repo_is_dirty(&self.repository) && !self.allow_dirty {
+        // Checking does not write anything, so it does not matter whether the repository is dirty
+        if !self.check && crate::util::repo_is_dirty(&self.repository) && !self.allow_dirty {
             return Err(Error::GitRepoDirty);
         }
 
-        let template_path = workdir
-            .join(config.fragment_dir())
-            .join(config.template_path());
-        let template_source = std::fs::read_to_string(template_path)?;
+        let template_source = load_template_source(workdir, config)?;
         let template = crate::template::new_handlebars(&template_source)?;
+        let changelogs = find_changelogs(workdir, config, self.package)?;
 
-        let suffix_path = workdir.join(config.fragment_dir()).join("suffix.md");
-        let suffix = match std::fs::read_to_string(&suffix_path) {
-            Ok(suffix) => Some(suffix),
-            Err(err) => {
-                match err.kind() {
-                    std::io::ErrorKind::NotFound => {
-                        // We don't want to spam the user for something they don't use
-                        tracing::trace!(
-                            "Did not find {}, not appending suffix",
-                            suffix_path.display()
-                        )
-                    }
-                    _ => {
-                        tracing::error!(
-                            "Could not read suffix file at {}: {err}",
-                            suffix_path.display()
-                        );
+        let mut out_of_date = false;
+        for (package, changelog_file_path) in changelogs {
+            let changelog_contents =
+                render_changelog(&template, workdir, config, package, self.all)?;
+
+            if self.check {
+                out_of_date |=
+                    !check_changelog(workdir, &changelog_file_path, &changelog_contents)?;
+                continue;
+            }
+
+            write_changelog(&changelog_file_path, &changelog_contents)?;
+        }
+
+        if out_of_date {
+            Ok(Some(std::process::ExitCode::FAILURE))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Generate the changelogs of the released fragments of `package`, or of all changelogs
+///
+/// Returns the paths of the written changelogs.
+pub(super) fn write_changelogs(
+    workdir: &Path,
+    config: &Configuration,
+    package: Option<String>,
+) -> Result<Vec<PathBuf>, Error> {
+    let template_source = load_template_source(workdir, config)?;
+    let template = crate::template::new_handlebars(&template_source)?;
+
+    let mut written = Vec::new();
+    for (package, changelog_file_path) in find_changelogs(workdir, config, package)? {
+        let changelog_contents = render_changelog(&template, workdir, config, package, false)?;
+        write_changelog(&changelog_file_path, &changelog_contents)?;
+        written.push(changelog_file_path);
+    }
+    Ok(written)
+}
+
+/// The `version` of `package` as the template will see it once the unreleased fragments are
+/// released with `metadata`
+///
+/// For a final version, the pre-releases that will be folded into it are included.
+pub(super) fn preview_version_data(
+    workdir: &Path,
+    config: &Configuration,
+    package: Option<&str>,
+    version: &semver::Version,
+    metadata: ReleaseMetadata,
+) -> Result<VersionData, Error> {
+    let fragment_dir = workdir.join(config.fragment_dir_for(package));
+    let template_data = generate_template_data(
+        load_release_files(fragment_dir.clone(), true),
+        None,
+        package.map(ToString::to_string),
+        true,
+    )?;
+
+    let version_string = version.to_string();
+    let mut entries = Vec::new();
+    let mut pre_releases = Vec::new();
+    for mut version_data in template_data.versions {
+        let is_folded_pre_release = version.pre.is_empty()
+            && version_data.version_as_semver().is_some_and(|pre_release| {
+                !pre_release.pre.is_empty()
+                    && pre_release.major == version.major
+                    && pre_release.minor == version.minor
+                    && pre_release.patch == version.patch
+            });
+
+        if version_data.version == "unreleased" {
+            entries.append(&mut version_data.entries);
+        } else if version_data.version == version_string {
+            load_release_metadata(
+                &mut version_data.pre_releases,
+                &fragment_dir.join(&version_string),
+            )?;
+            entries.append(&mut version_data.entries);
+            pre_releases.append(&mut version_data.pre_releases);
+        } else if is_folded_pre_release {
+            load_release_metadata(std::slice::from_mut(&mut version_data), &fragment_dir)?;
+            entries.extend(version_data.entries.iter().cloned());
+            pre_releases.push(version_data);
+        }
+    }
+    pre_releases.sort_by_key(VersionData::version_as_semver);
+
+    Ok(VersionData {
+        version: version_string,
+        pre_release: !version.pre.is_empty(),
+        entries,
+        pre_releases,
+        metadata,
+    })
+}
+
+fn load_template_source(workdir: &Path, config: &Configuration) -> Result<String, Error> {
+    let template_path = workdir
+        .join(config.fragment_dir())
+        .join(config.template_path());
+    Ok(std::fs::read_to_string(template_path)?)
+}
+
+/// The changelogs to generate, with the package they are generated for
+///
+/// Without a `package`, this is the changelog of the fragment directory and the changelogs of all
+/// workspace members that have their own fragments.
+fn find_changelogs(
+    workdir: &Path,
+    config: &Configuration,
+    package: Option<String>,
+) -> Result<Vec<(Option<String>, PathBuf)>, Error> {
+    match package {
+        Some(package) => {
+            let member = crate::command::common::find_workspace_member(workdir, &package)?;
+            Ok(vec![(
+                Some(package),
+                package_changelog_path(config, &member),
+            )])
+        }
+        None => {
+            let mut changelogs = vec![(None, workdir.join(config.changelog()))];
+            let packages = crate::command::common::find_packages_with_fragments(workdir, config)?;
+
+            if !packages.is_empty() {
+                let members = crate::command::common::find_workspace_members(workdir)?;
+                for package in packages {
+                    match members.iter().find(|pkg| pkg.name.as_str() == package) {
+                        Some(member) => {
+                            let path = package_changelog_path(config, member);
+                            changelogs.push((Some(package), path));
+                        }
+                        None => {
+                            tracing::warn!(
+                                "Not a workspace member, not generating changelog: {package}"
+                            );
+                        }
                     }
                 }
-                None
             }
-        };
 
-        let template_data =
-            generate_template_data(load_release_files(workdir, config, self.all), suffix)?;
+            Ok(changelogs)
+        }
+    }
+}
 
-        let changelog_contents =
-            template.render(crate::consts::INTERNAL_TEMPLATE_NAME, &template_data)?;
-        tracing::debug!("Rendered successfully");
+fn write_changelog(changelog_file_path: &Path, changelog_contents: &str) -> Result<(), Error> {
+    tracing::debug!(
+        "Writing changelog file now: {}",
+        changelog_file_path.display()
+    );
+    let mut changelog_file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(false)
+        .truncate(true)
+        .write(true)
+        .open(changelog_file_path)?;
+
+    write!(changelog_file, "{changelog_contents}")?;
+    changelog_file.sync_all()?;
+    Ok(())
+}
 
-        let changelog_file_path = workdir.join(config.changelog());
-        tracing::debug!(
-            "Writing changelog file now: {}",
-            changelog_file_path.display()
-        );
-        let mut changelog_file = std::fs::OpenOptions::new()
-            .create(true)
-            .append(false)
-            .truncate(true)
-            .write(true)
-            .open(changelog_file_path)?;
-
-        write!(changelog_file, "{changelog_contents}")?;
-        changelog_file.sync_all()?;
-        Ok(None)
+/// Compare the changelog file at `changelog_file_path` with `expected`
+///
+/// Prints a unified diff and returns `false` if they differ. A missing file counts as empty.
+fn check_changelog(
+    workdir: &Path,
+    changelog_file_path: &Path,
+    expected: &str,
+) -> Result<bool, Error> {
+    let actual = match std::fs::read_to_string(changelog_file_path) {
+        Ok(actual) => actual,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(Error::from(e)),
+    };
+
+    if actual == expected {
+        tracing::debug!("Up to date: {}", changelog_file_path.display());
+        return Ok(true);
     }
+
+    let display_path = changelog_file_path
+        .strip_prefix(workdir)
+        .unwrap_or(changelog_file_path)
+        .display();
+    let diff = similar::TextDiff::from_lines(actual.as_str(), expected);
+    print!(
+        "{}",
+        diff.unified_diff()
+            .header(&format!("a/{display_path}"), &format!("b/{display_path}"))
+    );
+    Ok(false)
 }
 
-fn load_release_files(
+/// The path of the changelog file of the workspace member `package`
+fn package_changelog_path(config: &Configuration, package: &cargo_metadata::Package) -> PathBuf {
+    let manifest_dir = package
+        .manifest_path
+        .parent()
+        .map(|dir| dir.as_std_path().to_path_buf())
+        .unwrap_or_default();
+
+    manifest_dir.join(config.package_changelog())
+}
+
+/// Render the changelog for the fragments of `package`, or of the fragment directory itself
+fn render_changelog(
+    template: &handlebars::Handlebars<'_>,
     workdir: &Path,
     config: &Configuration,
+    package: Option<String>,
     all: bool,
-) -> impl Iterator<Item = Result<(Option<semver::Version>, Fragment), Error>> {
-    let root_path = workdir.join(config.fragment_dir());
+) -> Result<String, Error> {
+    let fragment_dir = workdir.join(config.fragment_dir_for(package.as_deref()));
+
+    let suffix_path = fragment_dir.join("suffix.md");
+    let suffix = match std::fs::read_to_string(&suffix_path) {
+        Ok(suffix) => Some(suffix),
+        Err(err) => {
+            match err.kind() {
+                std::io::ErrorKind::NotFound => {
+                    // We don't want to spam the user for something they don't use
+                    tracing::trace!(
+                        "Did not find {}, not appending suffix",
+                        suffix_path.display()
+                    )
+                }
+                _ => {
+                    tracing::error!(
+                        "Could not read suffix file at {}: {err}",
+                        suffix_path.display()
+                    );
+                }
+            }
+            None
+        }
+    };
+
+    let mut template_data = generate_template_data(
+        load_release_files(fragment_dir.clone(), all),
+        suffix,
+        package,
+        config.show_pre_releases(),
+    )?;
+    load_release_metadata(&mut template_data.versions, &fragment_dir)?;
+
+    let changelog_contents =
+        template.render(crate::consts::INTERNAL_TEMPLATE_NAME, &template_data)?;
+    tracing::debug!("Rendered successfully");
+    Ok(changelog_contents)
+}
+
+fn load_release_files(
+    root_path: PathBuf,
+    all: bool,
+) -> impl Iterator<Item = Result<ReleaseFile, Error>> {
+    let fragment_dir_path = root_path.clone();
     walkdir::WalkDir::new(root_path.clone())
         .follow_links(false)
         .max_open(100)
@@ -103,7 +315,8 @@ fn load_release_files(
                     // Since the file itself comes from the workdir, this cannot fail?
                     let clean_path = de.path().strip_prefix(root_path.as_path()).unwrap();
                     if clean_path.components().count() <= 1
-                        || de.path().extension() != Some(OsStr::new("md"))
+                        || !is_release_or_unreleased_path(clean_path)
+                        || !crate::format::has_fragment_extension(de.path())
                     {
                         None
                     } else {
@@ -121,7 +334,15 @@ fn load_release_files(
                 Ok(de) => de,
             };
 
-            let version = match crate::command::common::get_version_from_path(de.path()) {
+            // Since the file itself comes from the workdir, this cannot fail?
+            let clean_path = de.path().strip_prefix(fragment_dir_path.as_path()).unwrap();
+            let pre_release =
+                match crate::command::common::get_folded_pre_release_from_path(clean_path) {
+                    Err(e) => return Some(Err(Error::from(e))),
+                    Ok(pre_release) => pre_release,
+                };
+
+            let version = match crate::command::common::get_version_from_path(clean_path) {
                 Err(e) => return Some(Err(Error::from(e))),
                 Ok(None) => {
                     if all {
@@ -141,22 +362,39 @@ fn load_release_files(
                 .map_err(Error::from)
                 .map(BufReader::new)
                 .and_then(|mut reader| {
-                    Fragment::from_reader(&mut reader)
+                    Fragment::from_file_reader(de.path(), &mut reader)
                         .map_err(|e| Error::Fragment(e, de.path().to_path_buf()))
                 });
 
             match fragment {
                 Err(e) => Some(Err(e)),
-                Ok(fragment) => Some(Ok((version, fragment))),
+                Ok(fragment) => Some(Ok((version, pre_release, fragment))),
             }
         })
 }
 
+/// Whether `path` (relative to the fragment directory) is in a release directory or the
+/// directory for unreleased fragments, and not in the fragment directory of a package
+fn is_release_or_unreleased_path(path: &Path) -> bool {
+    match path.components().next() {
+        Some(std::path::Component::Normal(first)) => first.to_str().is_some_and(|first| {
+            first == crate::consts::UNRELEASED_DIR_NAME || semver::Version::parse(first).is_ok()
+        }),
+        _ => false,
+    }
+}
+
+/// A fragment with the version it was released with, and the pre-release it was folded from
+type ReleaseFile = (Option<semver::Version>, Option<semver::Version>, Fragment);
+
 /// The data sent to the handlebars template
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, getset::Getters)]
 pub struct TemplateData {
     versions: Vec<VersionData>,
     suffix: Option<String>,
+
+    /// The name of the package the changelog is generated for, if any
+    package: Option<String>,
 }
 
 /// Helper type for storing version associated with Fragments
@@ -166,8 +404,24 @@ pub struct TemplateData {
 pub struct VersionData {
     #[getset(get = "pub")]
     version: String,
+
+    /// Whether the version is a pre-release, e.g. "1.2.0-rc.1"
+    #[getset(get = "pub")]
+    pre_release: bool,
+
     #[getset(get = "pub")]
     entries: Vec<Fragment>,
+
+    /// The pre-releases that were folded into this version when it was promoted
+    ///
+    /// Their entries are also part of `entries`.
+    #[getset(get = "pub")]
+    pre_releases: Vec<VersionData>,
+
+    /// The metadata recorded when the release was created, e.g. its `date`
+    #[getset(get = "pub")]
+    #[serde(flatten)]
+    metadata: ReleaseMetadata,
 }
 
 impl VersionData {
@@ -177,34 +431,84 @@ impl VersionData {
 }
 
 fn generate_template_data(
-    release_files: impl Iterator<Item = Result<(Option<semver::Version>, Fragment), Error>>,
+    release_files: impl Iterator<Item = Result<ReleaseFile, Error>>,
     suffix: Option<String>,
+    package: Option<String>,
+    show_pre_releases: bool,
 ) -> Result<TemplateData, Error> {
     let versions = {
         use itertools::Itertools;
         let mut hm = BTreeMap::new();
         for r in release_files {
-            let (version, fragment) = r?;
+            let (version, pre_release, fragment) = r?;
+
+            if version
+                .as_ref()
+                .is_some_and(|version| !version.pre.is_empty() && !show_pre_releases)
+            {
+                tracing::debug!("Hiding pre-release {version:?}");
+                continue;
+            }
 
-            if let Some(version) = version {
+            let (entries, pre_releases) = if let Some(version) = version {
                 hm.entry(version.to_string())
             } else {
                 hm.entry("unreleased".to_string())
             }
-            .or_insert_with(Vec::new)
-            .push(fragment);
+            .or_insert_with(|| (Vec::new(), BTreeMap::new()));
+
+            if let Some(pre_release) = pre_release {
+                pre_releases
+                    .entry(pre_release)
+                    .or_insert_with(Vec::new)
+                    .push(fragment.clone());
+            }
+            entries.push(fragment);
         }
         hm.into_iter()
-            .map(|(version, entries)| VersionData { version, entries })
+            .map(|(version, (entries, pre_releases))| VersionData {
+                pre_release: is_pre_release(&version),
+                version,
+                entries,
+                pre_releases: pre_releases
+                    .into_iter()
+                    .map(|(pre_release, entries)| VersionData {
+                        version: pre_release.to_string(),
+                        pre_release: true,
+                        entries,
+                        pre_releases: Vec::new(),
+                        metadata: ReleaseMetadata::default(),
+                    })
+                    .collect(),
+                metadata: ReleaseMetadata::default(),
+            })
             .sorted_by(|va, vb| va.version.cmp(&vb.version))
     };
 
     Ok(TemplateData {
         versions: versions.collect(),
         suffix,
+        package,
     })
 }
 
+/// Load the metadata of the `versions` from their release directories in `fragment_dir`
+fn load_release_metadata(versions: &mut [VersionData], fragment_dir: &Path) -> Result<(), Error> {
+    for version in versions {
+        let release_dir = fragment_dir.join(&version.version);
+        if let Some(metadata) = ReleaseMetadata::load(&release_dir)? {
+            version.metadata = metadata;
+        }
+        load_release_metadata(&mut version.pre_releases, &release_dir)?;
+    }
+
+    Ok(())
+}
+
+fn is_pre_release(version: &str) -> bool {
+    semver::Version::parse(version).is_ok_and(|version| !version.pre.is_empty())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::fragment::FragmentData;
@@ -218,6 +522,7 @@ mod tests {
             [
                 Ok((
                     Some(semver::Version::new(0, 2, 0)),
+                    None,
                     Fragment::new(
                         {
                             let mut hm = BTreeMap::new();
@@ -229,6 +534,7 @@ mod tests {
                 )),
                 Ok((
                     Some(semver::Version::new(0, 1, 0)),
+                    None,
                     Fragment::new(
                         {
                             let mut hm = BTreeMap::new();
@@ -241,6 +547,8 @@ mod tests {
             ]
             .into_iter(),
             None,
+            None,
+            true,
         );
 
         assert!(result.is_ok());
@@ -251,6 +559,47 @@ mod tests {
         assert_eq!(versions[1].version, "0.2.0");
     }
 
+    #[test]
+    fn test_template_data_folds_and_hides_pre_releases() {
+        let fragment = |text: &str| Fragment::new(BTreeMap::new(), text.to_string());
+        let release_files = || {
+            [
+                Ok((
+                    Some(semver::Version::parse("1.0.0").unwrap()),
+                    Some(semver::Version::parse("1.0.0-rc.1").unwrap()),
+                    fragment("from rc.1"),
+                )),
+                Ok((
+                    Some(semver::Version::parse("1.0.0").unwrap()),
+                    None,
+                    fragment("from final"),
+                )),
+                Ok((
+                    Some(semver::Version::parse("1.1.0-rc.1").unwrap()),
+                    None,
+                    fragment("from unpromoted rc"),
+                )),
+            ]
+            .into_iter()
+        };
+
+        let shown = generate_template_data(release_files(), None, None, true).unwrap();
+        assert_eq!(shown.versions.len(), 2);
+        assert_eq!(shown.versions[0].version, "1.0.0");
+        assert!(!shown.versions[0].pre_release);
+        assert_eq!(shown.versions[0].entries.len(), 2);
+        assert_eq!(shown.versions[0].pre_releases.len(), 1);
+        assert_eq!(shown.versions[0].pre_releases[0].version, "1.0.0-rc.1");
+        assert_eq!(shown.versions[0].pre_releases[0].entries.len(), 1);
+        assert_eq!(shown.versions[1].version, "1.1.0-rc.1");
+        assert!(shown.versions[1].pre_release);
+
+        let hidden = generate_template_data(release_files(), None, None, false).unwrap();
+        assert_eq!(hidden.versions.len(), 1);
+        assert_eq!(hidden.versions[0].version, "1.0.0");
+        assert_eq!(hidden.versions[0].pre_releases.len(), 1);
+    }
+
     #[test]
     fn default_template_renders_with_empty_data() {
         let hb = crate::template::new_handlebars(crate::consts::DEFAULT_TEMPLATE).unwrap();
@@ -273,6 +622,9 @@ mod tests {
             "versions".to_string(),
             vec![VersionData {
                 version: "0.1.0".to_string(),
+                pre_release: false,
+                pre_releases: Vec::new(),
+                metadata: ReleaseMetadata::default(),
                 entries: vec![Fragment::new(
                     {
                         let mut hdr = BTreeMap::new();
@@ -307,6 +659,9 @@ mod tests {
             "versions".to_string(),
             vec![VersionData {
                 version: "0.1.0".to_string(),
+                pre_release: false,
+                pre_releases: Vec::new(),
+                metadata: ReleaseMetadata::default(),
                 entries: vec![Fragment::new(
                     {
                         let mut hdr = BTreeMap::new();
@@ -328,6 +683,76 @@ mod tests {
         );
     }
 
+    #[test]
+    fn default_template_renders_release_date() {
+        let hb = crate::template::new_handlebars(crate::consts::DEFAULT_TEMPLATE).unwrap();
+        let mut data: BTreeMap<String, Vec<_>> = BTreeMap::new();
+        data.insert(
+            "versions".to_string(),
+            vec![VersionData {
+                version: "0.1.0".to_string(),
+                pre_release: false,
+                pre_releases: Vec::new(),
+                metadata: ReleaseMetadata::new(Some("2026-10-18".to_string()), None, None, None),
+                entries: Vec::new(),
+            }],
+        );
+        let template = hb.render(crate::consts::INTERNAL_TEMPLATE_NAME, &data);
+        assert!(template.is_ok(), "Not ok: {:?}", template.unwrap_err());
+        let template = template.unwrap();
+
+        assert!(
+            predicates::str::contains("## v0.1.0 - 2026-10-18").eval(&template),
+            "Does not contain '## v0.1.0 - 2026-10-18': {template}"
+        );
+    }
+
+    #[test]
+    fn template_can_access_list_and_map_headers() {
+        let hb = crate::template::new_handlebars(
+            "{{#each this.versions}}{{#each this.entries}}\
+             {{#each this.header.authors}}[{{this}}]{{/each}} {{this.header.links.issue}}\
+             {{/each}}{{/each}}",
+        )
+        .unwrap();
+        let mut data: BTreeMap<String, Vec<_>> = BTreeMap::new();
+        data.insert(
+            "versions".to_string(),
+            vec![VersionData {
+                version: "0.1.0".to_string(),
+                pre_release: false,
+                pre_releases: Vec::new(),
+                metadata: ReleaseMetadata::default(),
+                entries: vec![Fragment::new(
+                    {
+                        let mut hdr = BTreeMap::new();
+                        hdr.insert(
+                            "authors".to_string(),
+                            FragmentData::List(vec![
+                                FragmentData::Str("alice".to_string()),
+                                FragmentData::Str("bob".to_string()),
+                            ]),
+                        );
+                        hdr.insert(
+                            "links".to_string(),
+                            FragmentData::Map(BTreeMap::from([(
+                                "issue".to_string(),
+                                FragmentData::Str("https://example.com/1".to_string()),
+                            )])),
+                        );
+                        hdr
+                    },
+                    String::new(),
+                )],
+            }],
+        );
+
+        let rendered = hb
+            .render(crate::consts::INTERNAL_TEMPLATE_NAME, &data)
+            .unwrap();
+        assert_eq!(rendered, "[alice][bob] https://example.com/1");
+    }
+
     #[test]
     fn default_template_renders_versions_sorted() {
         let hb = crate::template::new_handlebars(crate::consts::DEFAULT_TEMPLATE).unwrap();
@@ -337,6 +762,9 @@ mod tests {
             vec![
                 VersionData {
                     version: "0.1.0".to_string(),
+                    pre_release: false,
+                    pre_releases: Vec::new(),
+                    metadata: ReleaseMetadata::default(),
                     entries: vec![Fragment::new(
                         {
                             let mut hdr = BTreeMap::new();
@@ -349,6 +777,9 @@ mod tests {
                 },
                 VersionData {
                     version: "0.2.0".to_string(),
+                    pre_release: false,
+                    pre_releases: Vec::new(),
+                    metadata: ReleaseMetadata::default(),
                     entries: vec![Fragment::new(
                         {
                             let mut hdr = BTreeMap::new();
@@ -1,8 +1,10 @@
 use crate::cli::{HasFormat, Selector};
+use crate::fragment::Fragment;
 
 #[derive(Debug, typed_builder::TypedBuilder)]
 pub struct HasCommand {
     format: Option<HasFormat>,
+    package: Option<String>,
     selector: Selector,
 }
 
@@ -12,8 +14,9 @@ impl crate::command::Command for HasCommand {
         workdir: &std::path::Path,
         config: &crate::config::Configuration,
     ) -> Result<Option<std::process::ExitCode>, crate::error::Error> {
-        let pathes =
-            crate::selector::SelectorExecutor::new(Some(&self.selector)).run(workdir, config)?;
+        let pathes = crate::selector::SelectorExecutor::new(Some(&self.selector))
+            .package(self.package.as_deref())
+            .run(workdir, config)?;
 
         match self.format.unwrap_or_default() {
             HasFormat::ExitCode => {
@@ -24,10 +27,33 @@ impl crate::command::Command for HasCommand {
                 }
             }
             HasFormat::Json => {
+                // A fragment that cannot be parsed is still reported, only without its ID, as
+                // `has` is not the place to validate fragments
+                let fragments = pathes
+                    .iter()
+                    .map(|path| {
+                        let id = std::fs::File::open(path)
+                            .map_err(crate::error::FragmentError::from)
+                            .map(std::io::BufReader::new)
+                            .and_then(|mut reader| Fragment::from_file_reader(path, &mut reader))
+                            .map(|fragment| fragment.id().clone())
+                            .unwrap_or_else(|e| {
+                                tracing::warn!("Cannot read the ID of {}: {e}", path.display());
+                                None
+                            });
+
+                        HasFragment {
+                            path: path.to_path_buf(),
+                            id,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
                 let reply = HasReply {
                     cargo_changelog: CargoChangelogMetadata::default(),
                     selector: self.selector.clone(),
                     pathes,
+                    fragments,
                 };
 
                 let reply = serde_json::to_string(&reply)?;
@@ -45,10 +71,18 @@ struct HasReply {
     cargo_changelog: CargoChangelogMetadata,
     selector: Selector,
     pathes: Vec<std::path::PathBuf>,
+    fragments: Vec<HasFragment>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct HasFragment {
+    path: std::path::PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
 }
 
 #[derive(Debug, serde::Serialize)]
-struct CargoChangelogMetadata {
+pub(super) struct CargoChangelogMetadata {
     version: String,
 }
 
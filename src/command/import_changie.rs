@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::import_command::{section_value, split_subject, Migration, NewHeaderField};
+use crate::config::Configuration;
+use crate::error::Error;
+use crate::fragment::{Fragment, FragmentData, FragmentDataType, FragmentDataTypeDefinite};
+
+/// The header field for the component of a changie fragment
+const COMPONENT_FIELD: &str = "component";
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChangieConfig {
+    #[serde(default = "changes_dir_default")]
+    changes_dir: PathBuf,
+
+    #[serde(default = "unreleased_dir_default")]
+    unreleased_dir: PathBuf,
+
+    #[serde(default)]
+    components: Vec<String>,
+
+    #[serde(default)]
+    kinds: Vec<Kind>,
+
+    #[serde(default)]
+    custom: Vec<Custom>,
+}
+
+fn changes_dir_default() -> PathBuf {
+    PathBuf::from(".changes")
+}
+
+fn unreleased_dir_default() -> PathBuf {
+    PathBuf::from("unreleased")
+}
+
+/// A kind of change, either just its label or a table with the label and an optional key
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum Kind {
+    Label(String),
+    Config { label: String, key: Option<String> },
+}
+
+impl Kind {
+    /// The value of the kind in the fragments
+    fn value(&self) -> &str {
+        match self {
+            Kind::Label(label) => label,
+            Kind::Config { key: Some(key), .. } => key,
+            Kind::Config { label, .. } => label,
+        }
+    }
+}
+
+/// A custom field of the fragments
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Custom {
+    key: String,
+
+    #[serde(rename = "type")]
+    ty: String,
+
+    #[serde(default)]
+    optional: bool,
+
+    #[serde(default)]
+    enum_options: Vec<String>,
+}
+
+impl Custom {
+    /// The header field for this custom field, header fields are lowercase by convention
+    fn header_name(&self) -> String {
+        self.key.to_lowercase()
+    }
+
+    fn fragment_type(&self) -> FragmentDataType {
+        match self.ty.as_str() {
+            "int" => FragmentDataType::Ty(FragmentDataTypeDefinite::Int),
+            "enum" => FragmentDataType::OneOf(self.enum_options.clone()),
+            _ => FragmentDataType::Ty(FragmentDataTypeDefinite::Str),
+        }
+    }
+}
+
+/// A fragment of changie
+#[derive(Debug, serde::Deserialize)]
+struct Change {
+    component: Option<String>,
+    kind: Option<String>,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    custom: BTreeMap<String, serde_norway::Value>,
+}
+
+/// Convert the unreleased fragments of changie, configured in `config_path` (a `.changie.yaml`)
+///
+/// The kind of a fragment becomes its type, mapped with the `sections` of the import settings, the
+/// first line of its body its subject and the rest of the body its text. The component and
+/// custom fields become header fields of the same (lowercase) name.
+pub(super) fn migrate(config_path: &Path, config: &Configuration) -> Result<Migration, Error> {
+    let changie = serde_norway::from_str::<ChangieConfig>(&std::fs::read_to_string(config_path)?)?;
+
+    let base_dir = config_path.parent().unwrap_or(Path::new("."));
+    let unreleased_dir = base_dir
+        .join(&changie.changes_dir)
+        .join(&changie.unreleased_dir);
+
+    let mut paths = std::fs::read_dir(&unreleased_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .filter(|path| {
+            path.as_ref().map_or(true, |path| {
+                path.extension()
+                    .is_some_and(|ext| ext == "yaml" || ext == "yml")
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.sort();
+
+    let settings = config.import();
+    let mut migration = Migration::default();
+    for path in paths {
+        let change = serde_norway::from_str::<Change>(&std::fs::read_to_string(&path)?)?;
+        let Some((subject, text)) = split_subject(&change.body) else {
+            migration
+                .unmapped
+                .push(format!("{}: Body is empty", path.display()));
+            continue;
+        };
+
+        let mut fragment = Fragment::empty();
+        let header = fragment.header_mut();
+        header.insert(settings.subject_field().clone(), FragmentData::Str(subject));
+        if let Some(kind) = change.kind {
+            header.insert(
+                settings.type_field().clone(),
+                FragmentData::Str(section_value(config, &kind)),
+            );
+        }
+        if let Some(component) = change.component {
+            header.insert(COMPONENT_FIELD.to_string(), FragmentData::Str(component));
+        }
+
+        for (key, value) in change.custom {
+            let Some(custom) = changie.custom.iter().find(|custom| custom.key == key) else {
+                migration.unmapped.push(format!(
+                    "{}: Custom field '{key}' is not configured, it was left out",
+                    path.display()
+                ));
+                continue;
+            };
+
+            let value = match value {
+                serde_norway::Value::String(s) => s,
+                serde_norway::Value::Number(n) => n.to_string(),
+                serde_norway::Value::Bool(b) => b.to_string(),
+                other => {
+                    migration.unmapped.push(format!(
+                        "{}: Custom field '{key}' has an unsupported value {other:?}",
+                        path.display()
+                    ));
+                    continue;
+                }
+            };
+            let data = match custom.fragment_type() {
+                FragmentDataType::Ty(FragmentDataTypeDefinite::Int) => match value.parse() {
+                    Ok(int) => FragmentData::Int(int),
+                    Err(_) => {
+                        migration.unmapped.push(format!(
+                            "{}: Custom field '{key}' is not a number: '{value}'",
+                            path.display()
+                        ));
+                        continue;
+                    }
+                },
+                _ => FragmentData::Str(value),
+            };
+            header.insert(custom.header_name(), data);
+        }
+
+        fragment.set_text(text);
+        fragment.generate_id();
+        migration.fragments.push((path, fragment));
+    }
+
+    if !changie.kinds.is_empty() {
+        let mut kinds = Vec::<String>::new();
+        for value in changie
+            .kinds
+            .iter()
+            .map(|kind| section_value(config, kind.value()))
+        {
+            if !kinds.contains(&value) {
+                kinds.push(value);
+            }
+        }
+        migration.header_fields.push(NewHeaderField {
+            name: settings.type_field().clone(),
+            fragment_type: FragmentDataType::OneOf(kinds),
+            required: true,
+        });
+    }
+    if !changie.components.is_empty() {
+        migration.header_fields.push(NewHeaderField {
+            name: COMPONENT_FIELD.to_string(),
+            fragment_type: FragmentDataType::OneOf(changie.components.clone()),
+            required: true,
+        });
+    }
+    migration
+        .header_fields
+        .extend(changie.custom.iter().map(|custom| NewHeaderField {
+            name: custom.header_name(),
+            fragment_type: custom.fragment_type(),
+            required: !custom.optional,
+        }));
+
+    Ok(migration)
+}
@@ -0,0 +1,597 @@
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+
+use crate::cli::ImportSource;
+use crate::config::Configuration;
+use crate::error::Error;
+use crate::fragment::{
+    Fragment, FragmentData, FragmentDataDesc, FragmentDataType, FragmentDataTypeDefinite,
+};
+use crate::release_metadata::ReleaseMetadata;
+
+/// Import a hand-written changelog, or the unreleased fragments of another tool, into fragments
+#[derive(Debug, typed_builder::TypedBuilder)]
+pub struct ImportCommand {
+    path: PathBuf,
+    from: ImportSource,
+}
+
+impl crate::command::Command for ImportCommand {
+    fn execute(
+        self,
+        workdir: &Path,
+        config: &Configuration,
+    ) -> Result<Option<std::process::ExitCode>, Error> {
+        let path = workdir.join(&self.path);
+        let migration = match self.from {
+            ImportSource::KeepAChangelog => return import_keep_a_changelog(&path, workdir, config),
+            ImportSource::Towncrier => super::import_towncrier::migrate(&path, config)?,
+            ImportSource::Changie => super::import_changie::migrate(&path, config)?,
+        };
+
+        // Like for a hand-written changelog, nothing is imported if anything cannot be imported
+        if !migration.unmapped.is_empty() {
+            println!("Nothing was imported from {}", self.path.display());
+            return Ok(report_unmapped(migration.unmapped));
+        }
+
+        let unreleased_dir = workdir
+            .join(config.fragment_dir())
+            .join(crate::consts::UNRELEASED_DIR_NAME);
+        std::fs::create_dir_all(&unreleased_dir)?;
+
+        let mut imported = 0;
+        for (source, fragment) in migration.fragments {
+            imported += 1;
+            tracing::debug!("Importing {}", source.display());
+            write_fragment(&unreleased_dir, &fragment, imported, config)?;
+        }
+        println!("Imported {imported} fragments from {}", self.path.display());
+
+        let mut header_fields = migration.header_fields;
+        header_fields.insert(
+            0,
+            NewHeaderField {
+                name: config.import().subject_field().clone(),
+                fragment_type: FragmentDataType::Ty(FragmentDataTypeDefinite::Str),
+                required: true,
+            },
+        );
+        header_fields.retain(|field| changes_header_fields(config.header_fields(), field));
+        if header_fields.is_empty() {
+            return Ok(None);
+        }
+
+        let config_path =
+            crate::config::find_config_file(workdir).ok_or(Error::ConfigDoesNotExist)?;
+        update_header_fields(&config_path, config.header_fields(), &header_fields)?;
+        println!(
+            "Updated header fields in {}: {}",
+            config_path.display(),
+            header_fields
+                .iter()
+                .map(|field| field.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        Ok(None)
+    }
+}
+
+/// Fragments converted from the unreleased fragments of another tool
+#[derive(Debug, Default)]
+pub(super) struct Migration {
+    /// The converted fragments, with the pathes they were converted from
+    pub(super) fragments: Vec<(PathBuf, Fragment)>,
+
+    /// The header fields that the converted fragments need
+    pub(super) header_fields: Vec<NewHeaderField>,
+
+    /// Everything that could not be converted
+    pub(super) unmapped: Vec<String>,
+}
+
+/// A header field to add to the configuration
+#[derive(Debug)]
+pub(super) struct NewHeaderField {
+    pub(super) name: String,
+    pub(super) fragment_type: FragmentDataType,
+    pub(super) required: bool,
+}
+
+/// Split the text of an entry into the subject (its first line) and the rest of the text
+pub(super) fn split_subject(body: &str) -> Option<(String, String)> {
+    let body = body.trim();
+    let (subject, text) = body.split_once('\n').unwrap_or((body, ""));
+    if subject.trim().is_empty() {
+        return None;
+    }
+    Some((subject.trim().to_string(), text.trim().to_string()))
+}
+
+fn import_keep_a_changelog(
+    path: &Path,
+    workdir: &Path,
+    config: &Configuration,
+) -> Result<Option<std::process::ExitCode>, Error> {
+    let display_path = path.strip_prefix(workdir).unwrap_or(path).display();
+    let source = std::fs::read_to_string(path)?;
+    let parsed = parse_keep_a_changelog(&source);
+    let fragment_dir = workdir.join(config.fragment_dir());
+
+    for version in parsed.versions.iter().filter_map(|v| v.version.as_ref()) {
+        let release_dir = fragment_dir.join(version.to_string());
+        if release_dir.exists() {
+            return Err(Error::ReleaseDirExists(release_dir));
+        }
+    }
+
+    // Convert everything first, to not leave a partial import behind if anything cannot be
+    // imported
+    let mut unmapped = parsed.unmapped;
+    let mut converted = Vec::with_capacity(parsed.versions.len());
+    for version in parsed.versions {
+        let mut fragments = Vec::with_capacity(version.entries.len());
+        for entry in &version.entries {
+            match entry_to_fragment(entry, config) {
+                Ok(fragment) => fragments.push(fragment),
+                Err(reason) => unmapped.push(Unmapped {
+                    line: entry.line,
+                    reason,
+                }),
+            }
+        }
+        converted.push((version, fragments));
+    }
+
+    if !unmapped.is_empty() {
+        println!("Nothing was imported from {display_path}");
+        unmapped.sort_by_key(|u| u.line);
+        return Ok(report_unmapped(
+            unmapped
+                .into_iter()
+                .map(|Unmapped { line, reason }| format!("{display_path}:{line}: {reason}"))
+                .collect(),
+        ));
+    }
+
+    let mut imported = 0;
+    for (version, fragments) in converted {
+        let dir = match version.version.as_ref() {
+            Some(version) => fragment_dir.join(version.to_string()),
+            None => fragment_dir.join(crate::consts::UNRELEASED_DIR_NAME),
+        };
+        std::fs::create_dir_all(&dir)?;
+
+        if version.version.is_some() {
+            let mut metadata = ReleaseMetadata::new(version.date, None, None, None);
+            metadata.set_yanked(version.yanked);
+            metadata.write(&dir)?;
+        }
+
+        for fragment in fragments {
+            imported += 1;
+            write_fragment(&dir, &fragment, imported, config)?;
+        }
+    }
+
+    println!("Imported {imported} entries from {display_path}");
+    Ok(None)
+}
+
+/// Print everything that could not be imported, which makes the command fail
+fn report_unmapped(unmapped: Vec<String>) -> Option<std::process::ExitCode> {
+    if unmapped.is_empty() {
+        return None;
+    }
+
+    eprintln!("Could not import:");
+    for problem in unmapped {
+        eprintln!("  {problem}");
+    }
+    Some(std::process::ExitCode::FAILURE)
+}
+
+/// Whether adding `field` changes the `existing` header fields
+///
+/// That is the case if the field does not exist yet, or if its list of allowed values is extended.
+pub(super) fn changes_header_fields(
+    existing: &IndexMap<String, FragmentDataDesc>,
+    field: &NewHeaderField,
+) -> bool {
+    match (
+        existing.get(&field.name).map(|desc| desc.fragment_type()),
+        &field.fragment_type,
+    ) {
+        (None, _) => true,
+        (Some(FragmentDataType::OneOf(old)), FragmentDataType::OneOf(new)) => {
+            new.iter().any(|value| !old.contains(value))
+        }
+        _ => false,
+    }
+}
+
+/// Add the `header_fields` to the configuration file at `config_path`, keeping its formatting
+///
+/// Fields that exist already are kept as they are, so that existing fragments stay valid. Only
+/// their list of allowed values is extended, if they have one.
+pub(super) fn update_header_fields(
+    config_path: &Path,
+    existing: &IndexMap<String, FragmentDataDesc>,
+    header_fields: &[NewHeaderField],
+) -> Result<(), Error> {
+    let source = std::fs::read_to_string(config_path)?;
+    let mut document = source.parse::<toml_edit::DocumentMut>()?;
+    let table = document
+        .entry("header_fields")
+        .or_insert(toml_edit::table())
+        .as_table_like_mut()
+        .ok_or_else(|| {
+            Error::InvalidImportSource(
+                config_path.to_path_buf(),
+                "'header_fields' is not a table".to_string(),
+            )
+        })?;
+
+    for field in header_fields {
+        let Some(desc) = existing.get(&field.name) else {
+            let mut field_table = toml_edit::InlineTable::new();
+            field_table.insert("type", type_value(&field.fragment_type));
+            field_table.insert("required", field.required.into());
+            table.insert(&field.name, toml_edit::value(field_table));
+            continue;
+        };
+
+        // Only extend the allowed values, changing the type or making a field required would
+        // invalidate existing fragments
+        let (FragmentDataType::OneOf(new), FragmentDataType::OneOf(old)) =
+            (&field.fragment_type, desc.fragment_type())
+        else {
+            continue;
+        };
+        if new.iter().all(|value| old.contains(value)) {
+            continue;
+        }
+        let mut allowed = old.clone();
+        allowed.extend(new.iter().filter(|value| !old.contains(value)).cloned());
+        if let Some(field_table) = table
+            .get_mut(&field.name)
+            .and_then(toml_edit::Item::as_table_like_mut)
+        {
+            field_table.insert(
+                "type",
+                toml_edit::value(type_value(&FragmentDataType::OneOf(allowed))),
+            );
+        }
+    }
+
+    std::fs::write(config_path, document.to_string())?;
+    Ok(())
+}
+
+fn type_value(fragment_type: &FragmentDataType) -> toml_edit::Value {
+    match fragment_type {
+        FragmentDataType::Ty(ty) => toml_edit::Value::from(ty.to_string()),
+        FragmentDataType::OneOf(allowed) => {
+            toml_edit::Value::Array(allowed.iter().collect::<toml_edit::Array>())
+        }
+    }
+}
+
+/// The value of the type field for the entries of `section`, from the configured `sections`
+///
+/// Sections are matched case-insensitively, sections that are not configured are used as is.
+pub(super) fn section_value(config: &Configuration, section: &str) -> String {
+    config
+        .import()
+        .sections()
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(section))
+        .map(|(_, value)| value.clone())
+        .unwrap_or_else(|| section.to_string())
+}
+
+/// Build the fragment for an entry, with the `type` header from the section of the entry
+fn entry_to_fragment(entry: &Entry, config: &Configuration) -> Result<Fragment, String> {
+    let settings = config.import();
+    let mut fragment = Fragment::empty();
+    fragment.header_mut().insert(
+        settings.subject_field().clone(),
+        FragmentData::Str(entry.subject.clone()),
+    );
+    fragment.set_text(entry.text.clone());
+
+    let type_field = settings.type_field();
+    let type_desc = config.header_fields().get(type_field);
+    match entry.section.as_deref() {
+        Some(section) => {
+            let Some(type_desc) = type_desc else {
+                return Err(format!(
+                    "Entry is in section '{section}', but '{type_field}' is not a header field"
+                ));
+            };
+
+            let value = section_value(config, section);
+            if let FragmentDataType::OneOf(allowed) = type_desc.fragment_type() {
+                if !allowed.contains(&value) {
+                    return Err(format!(
+                        "Section '{section}' maps to '{value}', which is not one of {allowed:?} for '{type_field}'"
+                    ));
+                }
+            }
+
+            fragment
+                .header_mut()
+                .insert(type_field.clone(), FragmentData::Str(value));
+        }
+        None if type_desc.is_some_and(|desc| desc.required()) => {
+            return Err(format!(
+                "Entry is not in a section, but '{type_field}' is required"
+            ));
+        }
+        None => {}
+    }
+
+    fragment.generate_id();
+    Ok(fragment)
+}
+
+fn write_fragment(
+    dir: &Path,
+    fragment: &Fragment,
+    number: usize,
+    config: &Configuration,
+) -> Result<(), Error> {
+    let format = crate::format::Format::from(config.edit_format());
+    let path =
+        crate::file_name::unique_path(dir, &format!("imported-{number:04}"), format.extension());
+    let mut file = std::fs::File::create(&path)?;
+    fragment
+        .write_to(&mut file, format)
+        .map_err(|e| Error::Fragment(e, path.to_path_buf()))?;
+    file.sync_all()?;
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+struct ParsedChangelog {
+    versions: Vec<Version>,
+    unmapped: Vec<Unmapped>,
+}
+
+/// A version heading with its entries, `None` for "Unreleased"
+#[derive(Debug)]
+struct Version {
+    version: Option<semver::Version>,
+    date: Option<String>,
+    yanked: bool,
+    entries: Vec<Entry>,
+}
+
+/// A bullet item of the changelog
+#[derive(Debug)]
+struct Entry {
+    line: usize,
+    section: Option<String>,
+    subject: String,
+    text: String,
+}
+
+/// A line (1-based) that could not be imported
+#[derive(Debug)]
+struct Unmapped {
+    line: usize,
+    reason: String,
+}
+
+/// Parse a changelog in the style of <https://keepachangelog.com>
+///
+/// Versions are "##" headings (e.g. "## [1.0.0] - 2026-10-18"), sections are "###" headings
+/// (e.g. "### Added") and entries are the bullet items in them. The first line of an item is its
+/// subject, the lines indented below it are its text. Everything before the first version, and
+/// link reference definitions, are skipped. Anything else is reported as unmapped.
+fn parse_keep_a_changelog(source: &str) -> ParsedChangelog {
+    let mut parsed = ParsedChangelog::default();
+    // Whether the current version heading could be parsed
+    let mut in_version = false;
+    let mut section = None;
+
+    for (idx, line) in source.lines().enumerate() {
+        let line_number = idx + 1;
+        let unmapped = |reason: String| Unmapped {
+            line: line_number,
+            reason,
+        };
+
+        if let Some(heading) = line.strip_prefix("## ") {
+            section = None;
+            match parse_version_heading(heading) {
+                Some(version) => {
+                    parsed.versions.push(version);
+                    in_version = true;
+                }
+                None => {
+                    parsed
+                        .unmapped
+                        .push(unmapped(format!("Not a version heading: '{line}'")));
+                    in_version = false;
+                }
+            }
+            continue;
+        }
+
+        if line.trim().is_empty() || is_link_reference(line) || parsed.versions.is_empty() {
+            continue;
+        }
+
+        if !in_version {
+            parsed
+                .unmapped
+                .push(unmapped(format!("Not below a version: '{line}'")));
+            continue;
+        }
+
+        // The version was pushed when its heading was parsed
+        let version = parsed.versions.last_mut().unwrap();
+
+        if let Some(heading) = line.strip_prefix("### ") {
+            section = Some(heading.trim().to_string());
+        } else if let Some(item) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|bullet| line.strip_prefix(bullet))
+        {
+            version.entries.push(Entry {
+                line: line_number,
+                section: section.clone(),
+                subject: item.trim().to_string(),
+                text: String::new(),
+            });
+        } else if let (true, Some(entry)) =
+            (line.starts_with([' ', '\t']), version.entries.last_mut())
+        {
+            if !entry.text.is_empty() {
+                entry.text.push('\n');
+            }
+            entry.text.push_str(dedent(line));
+        } else {
+            parsed
+                .unmapped
+                .push(unmapped(format!("Not a list item: '{line}'")));
+        }
+    }
+
+    parsed
+}
+
+/// Parse the text of a version heading, e.g. "[1.0.0] - 2026-10-18" or "Unreleased"
+fn parse_version_heading(heading: &str) -> Option<Version> {
+    let mut words = heading.split_whitespace();
+    let name = words.next()?.trim_start_matches('[').trim_end_matches(']');
+    let rest = words.collect::<Vec<_>>();
+
+    let version = if name.eq_ignore_ascii_case("unreleased") {
+        None
+    } else {
+        Some(semver::Version::parse(name.trim_start_matches('v')).ok()?)
+    };
+
+    let date = rest.iter().find_map(|word| {
+        let word = word.trim_matches(|c: char| !c.is_ascii_digit());
+        let date_format = time::macros::format_description!("[year]-[month]-[day]");
+        time::Date::parse(word, &date_format)
+            .is_ok()
+            .then(|| word.to_string())
+    });
+    let yanked = rest
+        .iter()
+        .any(|word| word.trim_matches(['[', ']']).eq_ignore_ascii_case("yanked"));
+
+    Some(Version {
+        version,
+        date,
+        yanked,
+        entries: Vec::new(),
+    })
+}
+
+/// Whether `line` is a link reference definition, e.g. "[1.0.0]: https://example.com"
+fn is_link_reference(line: &str) -> bool {
+    line.starts_with('[') && line.contains("]:")
+}
+
+/// Remove the indentation of a continuation line of a list item
+fn dedent(line: &str) -> &str {
+    let indent = line.len() - line.trim_start().len();
+    &line[indent.min(2)..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = indoc::indoc!(
+        r#"
+        # Changelog
+
+        All notable changes to this project will be documented in this file.
+
+        ## [Unreleased]
+        ### Added
+        - Something new
+
+        ## [1.1.0] - 2026-10-18
+        ### Added
+        - A feature
+          with more details
+          - and a nested item
+        ### Fixed
+        * A bug
+
+        Some paragraph
+
+        ## [1.0.0] - 2026-01-02 [YANKED]
+        - Not in a section
+
+        ## Old stuff
+        - Not in a version
+
+        [1.1.0]: https://example.com/compare/v1.0.0...v1.1.0
+        "#
+    );
+
+    #[test]
+    fn test_parse_versions() {
+        let parsed = parse_keep_a_changelog(CHANGELOG);
+        let versions = parsed
+            .versions
+            .iter()
+            .map(|v| {
+                (
+                    v.version.as_ref().map(ToString::to_string),
+                    v.date.clone(),
+                    v.yanked,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            versions,
+            vec![
+                (None, None, false),
+                (
+                    Some("1.1.0".to_string()),
+                    Some("2026-10-18".to_string()),
+                    false
+                ),
+                (
+                    Some("1.0.0".to_string()),
+                    Some("2026-01-02".to_string()),
+                    true
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_entries() {
+        let parsed = parse_keep_a_changelog(CHANGELOG);
+        let entries = &parsed.versions[1].entries;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].section.as_deref(), Some("Added"));
+        assert_eq!(entries[0].subject, "A feature");
+        assert_eq!(entries[0].text, "with more details\n- and a nested item");
+        assert_eq!(entries[1].section.as_deref(), Some("Fixed"));
+        assert_eq!(entries[1].subject, "A bug");
+
+        assert_eq!(parsed.versions[2].entries[0].section, None);
+    }
+
+    #[test]
+    fn test_parse_reports_unmapped_lines() {
+        let parsed = parse_keep_a_changelog(CHANGELOG);
+        let lines = parsed.unmapped.iter().map(|u| u.line).collect::<Vec<_>>();
+        // "Some paragraph", "## Old stuff" and the item below it
+        assert_eq!(lines, vec![17, 22, 23]);
+    }
+}
@@ -0,0 +1,229 @@
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+
+use super::import_command::{section_value, Migration, NewHeaderField};
+use crate::config::Configuration;
+use crate::error::Error;
+use crate::fragment::{Fragment, FragmentData, FragmentDataType, FragmentDataTypeDefinite};
+
+/// The fragment types of towncrier, if none are configured
+const DEFAULT_TYPES: &[&str] = &["feature", "bugfix", "doc", "removal", "misc"];
+
+/// The header field for the issue of a towncrier fragment
+const ISSUE_FIELD: &str = "issue";
+
+#[derive(Debug, serde::Deserialize)]
+struct ConfigFile {
+    tool: Option<Tool>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Tool {
+    towncrier: Option<TowncrierConfig>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct TowncrierConfig {
+    /// The fragment directory, relative to the configuration file
+    directory: Option<PathBuf>,
+
+    /// Types in the `[[tool.towncrier.type]]` style
+    #[serde(default, rename = "type")]
+    types: Vec<TowncrierType>,
+
+    /// Types in the `[tool.towncrier.fragment.<type>]` style
+    #[serde(default)]
+    fragment: IndexMap<String, toml::Value>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct TowncrierType {
+    directory: String,
+}
+
+/// Convert the fragments of towncrier, configured in `config_path` (a `towncrier.toml` or
+/// `pyproject.toml`)
+///
+/// Fragments are named like "123.bugfix.md", with the issue, the type and optionally a counter.
+/// The type is mapped with the `sections` of the import settings. The first line of a fragment
+/// is its subject, the rest its text. Issues that are not a number (e.g. "+orphan") are left out.
+pub(super) fn migrate(config_path: &Path, config: &Configuration) -> Result<Migration, Error> {
+    let invalid =
+        |reason: &str| Error::InvalidImportSource(config_path.to_path_buf(), reason.to_string());
+    let towncrier = toml::from_str::<ConfigFile>(&std::fs::read_to_string(config_path)?)?
+        .tool
+        .and_then(|tool| tool.towncrier)
+        .ok_or_else(|| invalid("no [tool.towncrier] section"))?;
+
+    let types = if !towncrier.types.is_empty() {
+        towncrier.types.into_iter().map(|ty| ty.directory).collect()
+    } else if !towncrier.fragment.is_empty() {
+        towncrier.fragment.into_keys().collect()
+    } else {
+        DEFAULT_TYPES
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    let base_dir = config_path.parent().unwrap_or(Path::new("."));
+    let fragment_dir = base_dir.join(
+        towncrier
+            .directory
+            .unwrap_or_else(|| PathBuf::from("newsfragments")),
+    );
+
+    let mut paths = std::fs::read_dir(&fragment_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.sort();
+
+    let settings = config.import();
+    let mut migration = Migration::default();
+    for path in paths {
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !path.is_file() || file_name.starts_with('.') {
+            continue;
+        }
+
+        let Some((issue, ty)) = parse_file_name(file_name, &types) else {
+            migration.unmapped.push(format!(
+                "{}: No fragment type in the file name",
+                path.display()
+            ));
+            continue;
+        };
+
+        let content = std::fs::read_to_string(&path)?;
+        let Some((subject, text)) = split_paragraph(&content) else {
+            migration
+                .unmapped
+                .push(format!("{}: Fragment is empty", path.display()));
+            continue;
+        };
+
+        let mut fragment = Fragment::empty();
+        let header = fragment.header_mut();
+        header.insert(settings.subject_field().clone(), FragmentData::Str(subject));
+        header.insert(
+            settings.type_field().clone(),
+            FragmentData::Str(section_value(config, ty)),
+        );
+        match issue.parse::<u64>() {
+            Ok(issue) => {
+                header.insert(ISSUE_FIELD.to_string(), FragmentData::Int(issue));
+            }
+            Err(_) if issue.starts_with('+') => {}
+            Err(_) => migration.unmapped.push(format!(
+                "{}: Issue '{issue}' is not a number, it was left out",
+                path.display()
+            )),
+        }
+        fragment.set_text(text);
+        fragment.generate_id();
+        migration.fragments.push((path, fragment));
+    }
+
+    let mut type_values = Vec::<String>::new();
+    for value in types.iter().map(|ty| section_value(config, ty)) {
+        if !type_values.contains(&value) {
+            type_values.push(value);
+        }
+    }
+    migration.header_fields = vec![
+        NewHeaderField {
+            name: settings.type_field().clone(),
+            fragment_type: FragmentDataType::OneOf(type_values),
+            required: true,
+        },
+        NewHeaderField {
+            name: ISSUE_FIELD.to_string(),
+            fragment_type: FragmentDataType::Ty(FragmentDataTypeDefinite::Int),
+            required: false,
+        },
+    ];
+    Ok(migration)
+}
+
+/// Find the issue and the type in a file name like "123.bugfix.md" or "+orphan.misc.1"
+fn parse_file_name<'a>(file_name: &'a str, types: &'a [String]) -> Option<(&'a str, &'a str)> {
+    let parts = file_name.split('.').collect::<Vec<_>>();
+    let type_index = parts
+        .iter()
+        .skip(1)
+        .position(|part| types.iter().any(|ty| ty == part))?
+        + 1;
+
+    let issue_len = parts[..type_index].join(".").len();
+    Some((&file_name[..issue_len], parts[type_index]))
+}
+
+/// Split the content of a fragment into the subject and the rest of the text
+///
+/// Towncrier fragments are usually one wrapped paragraph, so the subject is the whole first
+/// paragraph with its lines joined.
+fn split_paragraph(content: &str) -> Option<(String, String)> {
+    let mut lines = content.trim().lines();
+    let subject = lines
+        .by_ref()
+        .take_while(|line| !line.trim().is_empty())
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join(" ");
+    if subject.is_empty() {
+        return None;
+    }
+    Some((
+        subject,
+        lines.collect::<Vec<_>>().join("\n").trim().to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_file_name() {
+        let types = DEFAULT_TYPES
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            parse_file_name("123.bugfix.md", &types),
+            Some(("123", "bugfix"))
+        );
+        assert_eq!(
+            parse_file_name("+orphan.misc.1", &types),
+            Some(("+orphan", "misc"))
+        );
+        assert_eq!(
+            parse_file_name("some.name.feature", &types),
+            Some(("some.name", "feature"))
+        );
+        assert_eq!(parse_file_name("README.md", &types), None);
+        assert_eq!(parse_file_name("feature.md", &types), None);
+    }
+
+    #[test]
+    fn test_split_paragraph() {
+        assert_eq!(
+            split_paragraph("Fixed a crash when\nthe input is empty.\n"),
+            Some((
+                "Fixed a crash when the input is empty.".to_string(),
+                String::new()
+            ))
+        );
+        assert_eq!(
+            split_paragraph("Fixed a crash.\n\nIt happened\non empty input.\n"),
+            Some((
+                "Fixed a crash.".to_string(),
+                "It happened\non empty input.".to_string()
+            ))
+        );
+        assert_eq!(split_paragraph("\n  \n"), None);
+    }
+}
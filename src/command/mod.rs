@@ -6,9 +6,28 @@ mod common;
 
 mod add_command;
 pub use self::add_command::AddCommand;
+mod add_git;
 
 mod create_release_command;
 pub use self::create_release_command::CreateReleaseCommand;
+mod release_git;
+
+mod revert_release_command;
+pub use self::revert_release_command::RevertReleaseCommand;
+
+mod yank_command;
+pub use self::yank_command::YankCommand;
+
+mod import_command;
+pub use self::import_command::ImportCommand;
+mod import_changie;
+mod import_towncrier;
+
+mod from_commits_command;
+pub use self::from_commits_command::FromCommitsCommand;
+
+mod check_fragment_command;
+pub use self::check_fragment_command::CheckFragmentCommand;
 
 mod generate_changelog_command;
 pub use self::generate_changelog_command::GenerateChangelogCommand;
@@ -19,6 +38,7 @@ pub use self::show::Show;
 
 mod verify_metadata_command;
 pub use self::verify_metadata_command::VerifyMetadataCommand;
+mod verify_report;
 
 mod has;
 pub use self::has::HasCommand;
@@ -0,0 +1,196 @@
+use std::path::Path;
+
+use crate::command::VersionData;
+use crate::config::Configuration;
+use crate::error::Error;
+use crate::release_metadata::ReleaseMetadata;
+
+/// What the templates for the release commit message and the tag name can use
+#[derive(Debug, serde::Serialize)]
+struct ReleaseNames<'a> {
+    version: &'a str,
+    package: Option<&'a str>,
+    tag: Option<&'a str>,
+}
+
+/// What the template for the tag message can use: the version as for the changelog template
+#[derive(Debug, serde::Serialize)]
+struct ReleaseNotes<'a> {
+    #[serde(flatten)]
+    version: &'a VersionData,
+    package: Option<&'a str>,
+    tag: &'a str,
+}
+
+/// The name of the tag for the release of `version` of `package`, from `release.tag_name`
+pub(super) fn tag_name(
+    config: &Configuration,
+    version: &str,
+    package: Option<&str>,
+) -> Result<String, Error> {
+    let names = ReleaseNames {
+        version,
+        package,
+        tag: None,
+    };
+    let tag = render(config.release().tag_name(), &names)?;
+    let tag = tag.trim();
+    if !git2::Reference::is_valid_name(&format!("refs/tags/{tag}")) {
+        return Err(Error::InvalidTagName(tag.to_string()));
+    }
+    Ok(tag.to_string())
+}
+
+/// Fail if a release cannot be committed, before anything is changed
+///
+/// The repository has to be clean, so that the release commit only contains the release, and a
+/// committer has to be configured.
+pub(super) fn check_can_commit(repository: &git2::Repository) -> Result<(), Error> {
+    // Unlike for `generate`, untracked files count as well, because they would be committed
+    let is_clean = repository.state() == git2::RepositoryState::Clean
+        && repository
+            .statuses(Some(
+                git2::StatusOptions::new()
+                    .include_untracked(true)
+                    .include_ignored(false),
+            ))?
+            .is_empty();
+    if !is_clean {
+        return Err(Error::GitRepoDirty);
+    }
+
+    repository.signature().map_err(Error::GitCommit)?;
+    Ok(())
+}
+
+/// The commit message and the tag of a release, rendered before anything is changed
+#[derive(Debug)]
+pub(super) struct ReleaseMessages {
+    commit_message: String,
+
+    /// The name and the message of the tag, if releases are tagged
+    tag: Option<(String, String)>,
+}
+
+/// Render the commit message and the tag for the release of `version` of `package`
+///
+/// This runs before any fragment is moved, so that a broken template, an empty message or an
+/// existing tag leave everything as it was. The tag message is rendered from the unreleased
+/// fragments as they will be released with `metadata`.
+pub(super) fn prepare_release(
+    repository: &git2::Repository,
+    workdir: &Path,
+    config: &Configuration,
+    version: &semver::Version,
+    package: Option<&str>,
+    metadata: &ReleaseMetadata,
+) -> Result<ReleaseMessages, Error> {
+    let settings = config.release();
+    let version_string = version.to_string();
+    let tag = tag_name(config, &version_string, package)?;
+    let commit_message = render(
+        settings.commit_message(),
+        &ReleaseNames {
+            version: &version_string,
+            package,
+            tag: Some(&tag),
+        },
+    )?;
+    if commit_message.trim().is_empty() {
+        return Err(Error::CommitMessageEmpty);
+    }
+
+    if !settings.tag() {
+        return Ok(ReleaseMessages {
+            commit_message,
+            tag: None,
+        });
+    }
+
+    if repository
+        .find_reference(&format!("refs/tags/{tag}"))
+        .is_ok()
+    {
+        return Err(Error::TagExists(tag));
+    }
+
+    let version_data = super::generate_changelog_command::preview_version_data(
+        workdir,
+        config,
+        package,
+        version,
+        metadata.clone(),
+    )?;
+    let tag_message = render(
+        settings.tag_message(),
+        &ReleaseNotes {
+            version: &version_data,
+            package,
+            tag: &tag,
+        },
+    )?;
+    if tag_message.trim().is_empty() {
+        return Err(Error::TagMessageEmpty);
+    }
+
+    Ok(ReleaseMessages {
+        commit_message,
+        tag: Some((tag, tag_message)),
+    })
+}
+
+/// Regenerate the changelog, commit all changes as the release of `version` and tag the commit
+pub(super) fn commit_release(
+    repository: &git2::Repository,
+    workdir: &Path,
+    config: &Configuration,
+    version: &str,
+    package: Option<&str>,
+    messages: ReleaseMessages,
+) -> Result<(), Error> {
+    for changelog in super::generate_changelog_command::write_changelogs(
+        workdir,
+        config,
+        package.map(ToString::to_string),
+    )? {
+        tracing::info!("Generated {}", changelog.display());
+    }
+
+    let tree = repository
+        .index()
+        .and_then(|mut index| {
+            index.add_all(["*"], git2::IndexAddOption::DEFAULT, None)?;
+            index.update_all(["*"], None)?;
+            index.write()?;
+            index.write_tree()
+        })
+        .and_then(|oid| repository.find_tree(oid))
+        .map_err(Error::GitCommit)?;
+
+    let signature = repository.signature().map_err(Error::GitCommit)?;
+    let parent = repository.head()?.peel_to_commit()?;
+    let oid = repository
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            &messages.commit_message,
+            &tree,
+            &[&parent],
+        )
+        .map_err(Error::GitCommit)?;
+    println!("Committed release {version} as {oid}");
+
+    if let Some((tag, tag_message)) = messages.tag {
+        let commit = repository.find_object(oid, Some(git2::ObjectType::Commit))?;
+        repository.tag(&tag, &commit, &signature, &tag_message, false)?;
+        println!("Tagged release {version} as {tag}");
+    }
+
+    Ok(())
+}
+
+fn render<T: serde::Serialize>(template_source: &str, data: &T) -> Result<String, Error> {
+    let template = crate::template::new_handlebars(template_source)?;
+    Ok(template.render(crate::consts::INTERNAL_TEMPLATE_NAME, data)?)
+}
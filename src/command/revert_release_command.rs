@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use crate::{config::Configuration, error::Error};
+
+#[derive(typed_builder::TypedBuilder)]
+pub struct RevertReleaseCommand {
+    repository: git2::Repository,
+    version: String,
+    force: bool,
+    package: Option<String>,
+}
+
+impl std::fmt::Debug for RevertReleaseCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RevertReleaseCommand")
+            .field("repository", &self.repository.workdir())
+            .field("version", &self.version)
+            .field("force", &self.force)
+            .field("package", &self.package)
+            .finish()
+    }
+}
+
+impl crate::command::Command for RevertReleaseCommand {
+    fn execute(
+        self,
+        workdir: &Path,
+        config: &Configuration,
+    ) -> Result<Option<std::process::ExitCode>, Error> {
+        let package = self.package.as_deref();
+        let fragment_dir = workdir.join(config.fragment_dir_for(package));
+        let release_dir = fragment_dir.join(&self.version);
+        let unreleased_dir = fragment_dir.join(crate::consts::UNRELEASED_DIR_NAME);
+
+        if !release_dir.is_dir() {
+            return Err(Error::ReleaseDoesNotExist(self.version));
+        }
+
+        if let Some(tag) = find_release_tag(&self.repository, config, &self.version, package)? {
+            if self.force {
+                tracing::warn!(
+                    "Reverting release {} although it is tagged as '{tag}'",
+                    self.version
+                );
+            } else {
+                return Err(Error::ReleaseTagged(self.version, tag));
+            }
+        }
+
+        // Fragments go back to the unreleased fragments, pre-releases that were folded into the
+        // release go back to the fragment directory.
+        let mut to_be_moved = Vec::new();
+        for entry in std::fs::read_dir(&release_dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let file_name = entry.file_name();
+
+            if file_name == crate::consts::RELEASE_METADATA_FILE_NAME {
+                continue;
+            }
+
+            let destination = if entry.file_type()?.is_dir() {
+                fragment_dir.join(&file_name)
+            } else {
+                unreleased_dir.join(&file_name)
+            };
+
+            if destination.exists() {
+                return Err(Error::FileExists(destination));
+            }
+
+            to_be_moved.push((path, destination));
+        }
+
+        std::fs::create_dir_all(&unreleased_dir)?;
+        for (entry, destination) in to_be_moved {
+            tracing::info!("Moving: {} -> {}", entry.display(), destination.display());
+            std::fs::rename(entry, destination)?;
+        }
+
+        let metadata_path = release_dir.join(crate::consts::RELEASE_METADATA_FILE_NAME);
+        if metadata_path.exists() {
+            std::fs::remove_file(metadata_path)?;
+        }
+        std::fs::remove_dir(&release_dir)?;
+
+        Ok(None)
+    }
+}
+
+/// Find the git tag of the release of `version`, if there is any
+///
+/// Besides the configured `tag_name` pattern, common tag names like "v1.2.3" are tried.
+fn find_release_tag(
+    repository: &git2::Repository,
+    config: &Configuration,
+    version: &str,
+    package: Option<&str>,
+) -> Result<Option<String>, Error> {
+    let mut candidates = match package {
+        None => vec![format!("v{version}"), version.to_string()],
+        Some(package) => vec![
+            format!("{package}-v{version}"),
+            format!("{package}-{version}"),
+            format!("{package}@{version}"),
+        ],
+    };
+    match super::release_git::tag_name(config, version, package) {
+        Ok(tag) if !candidates.contains(&tag) => candidates.insert(0, tag),
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Not looking for the configured tag name: {e}"),
+    }
+
+    for candidate in candidates {
+        match repository.find_reference(&format!("refs/tags/{candidate}")) {
+            Ok(_) => return Ok(Some(candidate)),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => continue,
+            Err(e) => return Err(Error::from(e)),
+        }
+    }
+
+    Ok(None)
+}
@@ -18,6 +18,7 @@ use crate::{
 #[derive(Debug, typed_builder::TypedBuilder)]
 pub struct Show {
     format: Option<crate::cli::ShowFormat>,
+    package: Option<String>,
     selector: Option<Selector>,
 }
 
@@ -27,8 +28,9 @@ impl crate::command::Command for Show {
         workdir: &Path,
         config: &Configuration,
     ) -> Result<Option<std::process::ExitCode>, Error> {
-        let pathes =
-            crate::selector::SelectorExecutor::new(self.selector.as_ref()).run(workdir, config)?;
+        let pathes = crate::selector::SelectorExecutor::new(self.selector.as_ref())
+            .package(self.package.as_deref())
+            .run(workdir, config)?;
 
         tracing::trace!("Looking at: {pathes:?}");
         let fragments = pathes.into_iter().map(|path| {
@@ -40,7 +42,7 @@ impl crate::command::Command for Show {
                 .map_err(FragmentError::from)
                 .map(BufReader::new)
                 .and_then(|mut reader| {
-                    Fragment::from_reader(&mut reader).map(|f| (path.to_path_buf(), f))
+                    Fragment::from_file_reader(&path, &mut reader).map(|f| (path.to_path_buf(), f))
                 })
                 .map_err(|e| Error::Fragment(e, path.to_path_buf()))
         });
@@ -68,6 +70,9 @@ fn pretty_print(
     iter.try_for_each(|fragment| {
         let (path, fragment) = fragment?;
         writeln!(output, "{}", Paint::new(path.display()).bold())?;
+        if let Some(id) = fragment.id() {
+            writeln!(output, "{key}: {id}", key = Paint::new("id").italic())?;
+        }
         fragment.header().iter().try_for_each(|(key, value)| {
             writeln!(
                 output,
@@ -1,15 +1,19 @@
-use std::{io::BufReader, path::Path};
+use std::path::{Path, PathBuf};
 
 use itertools::Itertools;
 
 use crate::{
+    cli::VerifyFormat,
+    command::verify_report::{self, VerificationRecord},
     config::Configuration,
-    error::{Error, FragmentError, VerificationError},
+    error::{Error, VerificationError},
     fragment::Fragment,
 };
 
 #[derive(Debug, typed_builder::TypedBuilder)]
-pub struct VerifyMetadataCommand {}
+pub struct VerifyMetadataCommand {
+    format: VerifyFormat,
+}
 
 impl crate::command::Command for VerifyMetadataCommand {
     fn execute(
@@ -17,39 +21,112 @@ impl crate::command::Command for VerifyMetadataCommand {
         workdir: &Path,
         config: &Configuration,
     ) -> Result<Option<std::process::ExitCode>, Error> {
-        let (_oks, errors): (Vec<_>, Vec<VerificationError>) =
+        let (checked, errors): (Vec<Option<PathBuf>>, Vec<VerificationError>) =
             walkdir::WalkDir::new(workdir.join(config.fragment_dir()))
                 .follow_links(false)
                 .max_open(100)
                 .same_file_system(true)
                 .into_iter()
                 .map(|rde| {
-                    rde.map(|de| verify_entry(de.path()))
-                        .map_err(VerificationError::from)
+                    rde.map_err(VerificationError::from).and_then(|de| {
+                        if is_fragment_file(&de) {
+                            verify_entry(de.path(), config).map(|_| Some(de.into_path()))
+                        } else {
+                            Ok(None)
+                        }
+                    })
                 })
                 .partition_result();
 
-        if !errors.is_empty() {
-            Err(Error::Verification(errors))
-        } else {
+        if self.format == VerifyFormat::Text {
+            return if errors.is_empty() {
+                Ok(None)
+            } else {
+                Err(Error::Verification(errors))
+            };
+        }
+
+        let checked = checked
+            .into_iter()
+            .flatten()
+            .chain(
+                errors
+                    .iter()
+                    .filter_map(VerificationError::path)
+                    .map(Path::to_path_buf),
+            )
+            .map(|path| {
+                path.strip_prefix(workdir)
+                    .map(Path::to_path_buf)
+                    .unwrap_or(path)
+            })
+            .sorted()
+            .dedup()
+            .collect::<Vec<_>>();
+        let records = VerificationRecord::from_errors(workdir, &errors);
+
+        let out = std::io::stdout();
+        let output = out.lock();
+        match self.format {
+            VerifyFormat::Text => unreachable!(), // handled above
+            VerifyFormat::Json => verify_report::write_json(output, &checked, &records)?,
+            VerifyFormat::Junit => verify_report::write_junit(output, &checked, &records)?,
+            VerifyFormat::Sarif => verify_report::write_sarif(output, &records)?,
+        }
+
+        if errors.is_empty() {
             Ok(None)
+        } else {
+            Ok(Some(std::process::ExitCode::FAILURE))
         }
     }
 }
 
-fn verify_entry(entry: &std::path::Path) -> Result<(), VerificationError> {
+/// Whether `entry` is a fragment, i.e. a file in the unreleased or in a release directory
+///
+/// This skips the template, suffix and release metadata files, as well as `.gitkeep` files.
+pub(super) fn is_fragment_file(entry: &walkdir::DirEntry) -> bool {
+    if !entry.file_type().is_file() {
+        return false;
+    }
+
+    let file_name = entry.file_name();
+    if file_name == ".gitkeep"
+        || file_name == crate::consts::RELEASE_METADATA_FILE_NAME
+        || !crate::format::has_fragment_extension(entry.path())
+    {
+        return false;
+    }
+
+    entry
+        .path()
+        .parent()
+        .and_then(Path::file_name)
+        .and_then(|dir_name| dir_name.to_str())
+        .is_some_and(|dir_name| {
+            dir_name == crate::consts::UNRELEASED_DIR_NAME
+                || semver::Version::parse(dir_name).is_ok()
+        })
+}
+
+pub(super) fn verify_entry(entry: &Path, config: &Configuration) -> Result<(), VerificationError> {
     if crate::command::common::get_version_from_path(entry)?.is_none() {
-        tracing::warn!("No version: {}", entry.display());
+        tracing::debug!("No version: {}", entry.display());
     }
 
-    std::fs::OpenOptions::new()
-        .read(true)
-        .create(false)
-        .write(false)
-        .open(entry)
-        .map_err(FragmentError::from)
-        .map(BufReader::new)
-        .and_then(|mut reader| Fragment::from_reader(&mut reader))
-        .map_err(|e| VerificationError::FragmentParsing(entry.to_path_buf(), e))
-        .map(|_| ())
+    let source = std::fs::read_to_string(entry)
+        .map_err(|e| VerificationError::Io(entry.to_path_buf(), e))?;
+
+    let fragment = Fragment::from_file_reader(entry, &mut source.as_bytes())
+        .map_err(|e| VerificationError::FragmentParsing(entry.to_path_buf(), e))?;
+
+    let errors = crate::schema::validate_header(entry, &source, &fragment, config.header_fields());
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(VerificationError::Header {
+            path: entry.to_path_buf(),
+            errors,
+        })
+    }
 }
@@ -0,0 +1,376 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, HeaderError, VerificationError};
+
+/// A single problem found by `verify-metadata`, in a form that can be serialized for CI tools
+#[derive(Debug, serde::Serialize)]
+pub struct VerificationRecord {
+    path: Option<PathBuf>,
+    line: Option<usize>,
+    column: Option<usize>,
+    field: Option<String>,
+    rule: &'static str,
+    message: String,
+}
+
+/// The rules a record can be reported for, with a short description for SARIF
+const RULES: &[(&str, &str)] = &[
+    ("parse", "Fragment cannot be parsed"),
+    ("io", "Fragment cannot be read"),
+    ("walk", "Fragment directory cannot be read"),
+    ("version", "Release directory name is not valid"),
+    ("required", "Required header field is missing"),
+    ("type", "Header field has the wrong type"),
+    ("one-of", "Header field value is not allowed"),
+    (
+        "invalid-value",
+        "Header field value is not valid for its type",
+    ),
+    (
+        "constraint",
+        "Header field value violates a configured constraint",
+    ),
+    ("unknown-field", "Header field is not configured"),
+];
+
+impl VerificationRecord {
+    /// Turn the errors of `verify-metadata` into records, with pathes relative to `workdir`
+    pub fn from_errors(workdir: &Path, errors: &[VerificationError]) -> Vec<Self> {
+        let relative = |path: &Path| path.strip_prefix(workdir).unwrap_or(path).to_path_buf();
+
+        errors
+            .iter()
+            .flat_map(|error| match error {
+                VerificationError::Header { path, errors } => errors
+                    .iter()
+                    .map(|header_error| Self::from_header_error(relative(path), header_error))
+                    .collect::<Vec<_>>(),
+                other => vec![Self::from_verification_error(&relative, other)],
+            })
+            .collect()
+    }
+
+    fn from_verification_error(
+        relative: &dyn Fn(&Path) -> PathBuf,
+        error: &VerificationError,
+    ) -> Self {
+        let rule = match error {
+            VerificationError::Version(_) => "version",
+            VerificationError::FragmentParsing(..) | VerificationError::Header { .. } => "parse",
+            VerificationError::WalkDir(_) => "walk",
+            VerificationError::Io(..) => "io",
+        };
+
+        Self {
+            path: error.path().map(relative),
+            line: None,
+            column: None,
+            field: None,
+            rule,
+            message: error_chain_message(error),
+        }
+    }
+
+    fn from_header_error(path: PathBuf, error: &HeaderError) -> Self {
+        let (field, rule, src, span) = match error {
+            HeaderError::MissingRequiredField { field, src, span } => {
+                (field, "required", src, span)
+            }
+            HeaderError::TypeMismatch {
+                field, src, span, ..
+            } => (field, "type", src, span),
+            HeaderError::ValueNotAllowed {
+                field, src, span, ..
+            } => (field, "one-of", src, span),
+            HeaderError::InvalidValue {
+                field, src, span, ..
+            } => (field, "invalid-value", src, span),
+            HeaderError::ConstraintViolation {
+                field, src, span, ..
+            } => (field, "constraint", src, span),
+            HeaderError::UnknownField { field, src, span } => (field, "unknown-field", src, span),
+        };
+
+        let (line, column) = line_and_column(src.inner(), span.offset());
+        Self {
+            path: Some(path),
+            line: Some(line),
+            column: Some(column),
+            field: Some(field.clone()),
+            rule,
+            message: error.to_string(),
+        }
+    }
+}
+
+/// The 1-based line and column of the byte `offset` in `source`
+fn line_and_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map(|line| line.chars().count())
+        .unwrap_or(0)
+        + 1;
+    (line, column)
+}
+
+fn error_chain_message(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(error) = source {
+        message.push_str(": ");
+        message.push_str(&error.to_string());
+        source = error.source();
+    }
+    message
+}
+
+#[derive(Debug, serde::Serialize)]
+struct JsonReport<'a> {
+    #[serde(rename = "cargo-changelog")]
+    cargo_changelog: super::has::CargoChangelogMetadata,
+    checked: &'a [PathBuf],
+    errors: &'a [VerificationRecord],
+}
+
+/// Write the records as a JSON object, next to the list of fragments that were checked
+pub fn write_json<W: Write>(
+    mut writer: W,
+    checked: &[PathBuf],
+    records: &[VerificationRecord],
+) -> Result<(), Error> {
+    let report = JsonReport {
+        cargo_changelog: super::has::CargoChangelogMetadata::default(),
+        checked,
+        errors: records,
+    };
+    serde_json::to_writer(&mut writer, &report)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Write the records as a JUnit XML report, with one test case per checked fragment
+pub fn write_junit<W: Write>(
+    mut writer: W,
+    checked: &[PathBuf],
+    records: &[VerificationRecord],
+) -> Result<(), Error> {
+    let mut cases = checked
+        .iter()
+        .map(|path| (Some(path.clone()), Vec::new()))
+        .collect::<Vec<(Option<PathBuf>, Vec<&VerificationRecord>)>>();
+
+    for record in records {
+        match cases.iter_mut().find(|(path, _)| *path == record.path) {
+            Some((_, failures)) => failures.push(record),
+            None => cases.push((record.path.clone(), vec![record])),
+        }
+    }
+
+    let failures = cases.iter().filter(|(_, f)| !f.is_empty()).count();
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<testsuites name="cargo-changelog" tests="{tests}" failures="{failures}">"#,
+        tests = cases.len()
+    )?;
+    writeln!(
+        writer,
+        r#"  <testsuite name="verify-metadata" tests="{tests}" failures="{failures}">"#,
+        tests = cases.len()
+    )?;
+
+    for (path, failures) in cases {
+        let name = path
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        let name = xml_escape(&name);
+
+        if failures.is_empty() {
+            writeln!(
+                writer,
+                r#"    <testcase name="{name}" classname="verify-metadata"/>"#
+            )?;
+            continue;
+        }
+
+        writeln!(
+            writer,
+            r#"    <testcase name="{name}" classname="verify-metadata">"#
+        )?;
+        for record in failures {
+            let location = match (record.line, record.column) {
+                (Some(line), Some(column)) => format!("{name}:{line}:{column}: "),
+                _ => String::new(),
+            };
+            writeln!(
+                writer,
+                r#"      <failure type="{rule}" message="{message}">{location}{message}</failure>"#,
+                rule = record.rule,
+                message = xml_escape(&record.message),
+            )?;
+        }
+        writeln!(writer, "    </testcase>")?;
+    }
+
+    writeln!(writer, "  </testsuite>")?;
+    writeln!(writer, "</testsuites>")?;
+    Ok(())
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Write the records as a SARIF 2.1.0 log, e.g. for code scanning annotations
+pub fn write_sarif<W: Write>(mut writer: W, records: &[VerificationRecord]) -> Result<(), Error> {
+    let rules = RULES
+        .iter()
+        .map(|(id, description)| {
+            serde_json::json!({
+                "id": id,
+                "shortDescription": { "text": description },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let results = records
+        .iter()
+        .map(|record| {
+            let mut result = serde_json::json!({
+                "ruleId": record.rule,
+                "level": "error",
+                "message": { "text": record.message },
+            });
+
+            if let Some(path) = record.path.as_ref() {
+                let mut location = serde_json::json!({
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": path_to_uri(path) },
+                    }
+                });
+                if let (Some(line), Some(column)) = (record.line, record.column) {
+                    location["physicalLocation"]["region"] = serde_json::json!({
+                        "startLine": line,
+                        "startColumn": column,
+                    });
+                }
+                result["locations"] = serde_json::json!([location]);
+            }
+
+            result
+        })
+        .collect::<Vec<_>>();
+
+    let log = serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    });
+
+    serde_json::to_writer_pretty(&mut writer, &log)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+fn path_to_uri(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_and_column() {
+        let source = "+++\nfoo = 1\n+++\n";
+        assert_eq!(line_and_column(source, 0), (1, 1));
+        assert_eq!(line_and_column(source, 4), (2, 1));
+        assert_eq!(line_and_column(source, 10), (2, 7));
+    }
+
+    #[test]
+    fn test_header_error_rules_are_listed() {
+        let src = || miette::NamedSource::new("fragment.md", String::new());
+        let span = || miette::SourceSpan::from((0, 0));
+        let field = || "field".to_string();
+        let errors = [
+            HeaderError::MissingRequiredField {
+                field: field(),
+                src: src(),
+                span: span(),
+            },
+            HeaderError::TypeMismatch {
+                field: field(),
+                expected: "int".to_string(),
+                found: "string".to_string(),
+                src: src(),
+                span: span(),
+            },
+            HeaderError::ValueNotAllowed {
+                field: field(),
+                value: "value".to_string(),
+                allowed: Vec::new(),
+                src: src(),
+                span: span(),
+            },
+            HeaderError::InvalidValue {
+                field: field(),
+                value: "value".to_string(),
+                expected: "date".to_string(),
+                reason: "reason".to_string(),
+                src: src(),
+                span: span(),
+            },
+            HeaderError::ConstraintViolation {
+                field: field(),
+                reason: "reason".to_string(),
+                src: src(),
+                span: span(),
+            },
+            HeaderError::UnknownField {
+                field: field(),
+                src: src(),
+                span: span(),
+            },
+        ];
+
+        for error in &errors {
+            let record = VerificationRecord::from_header_error(PathBuf::from("fragment.md"), error);
+            assert!(
+                RULES.iter().any(|(rule, _)| *rule == record.rule),
+                "Rule '{}' is not in RULES",
+                record.rule
+            );
+        }
+    }
+
+    #[test]
+    fn test_xml_escape() {
+        assert_eq!(
+            xml_escape(r#"Value '<a>' & "b""#),
+            "Value &apos;&lt;a&gt;&apos; &amp; &quot;b&quot;"
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use std::path::Path;
+
+use crate::{config::Configuration, error::Error, release_metadata::ReleaseMetadata};
+
+/// Set the `yanked` flag in the metadata of a release
+#[derive(Debug, typed_builder::TypedBuilder)]
+pub struct YankCommand {
+    version: String,
+    undo: bool,
+    package: Option<String>,
+}
+
+impl crate::command::Command for YankCommand {
+    fn execute(
+        self,
+        workdir: &Path,
+        config: &Configuration,
+    ) -> Result<Option<std::process::ExitCode>, Error> {
+        let release_dir = workdir
+            .join(config.fragment_dir_for(self.package.as_deref()))
+            .join(&self.version);
+        if !release_dir.is_dir() {
+            return Err(Error::ReleaseDoesNotExist(self.version));
+        }
+
+        let mut metadata = ReleaseMetadata::load(&release_dir)?.unwrap_or_default();
+        metadata.set_yanked(!self.undo);
+        metadata.write(&release_dir)?;
+
+        if self.undo {
+            println!("Release {} is not yanked anymore", self.version);
+        } else {
+            println!("Yanked release {}", self.version);
+        }
+        Ok(None)
+    }
+}
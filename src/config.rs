@@ -5,7 +5,9 @@ use indexmap::IndexMap;
 use miette::IntoDiagnostic;
 
 use crate::error::Error;
+use crate::fragment::FragmentData;
 use crate::fragment::FragmentDataDesc;
+use crate::version::Bump;
 
 pub const CONFIG_FILE_NAMES: &[&str] = &[".changelog.toml", "changelog.toml"];
 pub const CONFIG_FILE_DEFAULT_NAME: &str = CONFIG_FILE_NAMES[1];
@@ -13,6 +15,8 @@ pub const DEFAULT_CONFIG: &str = include_str!("../assets/default_config.toml");
 
 #[derive(Debug, getset::Getters, getset::CopyGetters, serde::Deserialize, serde::Serialize)]
 pub struct Configuration {
+    /// Whether to record the date of a release in its metadata
+    #[getset(get_copy = "pub")]
     add_version_date: bool,
 
     /// Directory name where fragments will be stored
@@ -48,11 +52,43 @@ pub struct Configuration {
     #[serde(default = "changelog_default")]
     changelog: PathBuf,
 
+    /// The path of the changelog file of a package, relative to the directory of its Cargo.toml
+    ///
+    /// Only used for packages that have their own fragments (see `add --package`).
+    ///
+    /// By default: "CHANGELOG.md"
+    #[getset(get = "pub")]
+    #[serde(default = "changelog_default")]
+    package_changelog: PathBuf,
+
+    /// Whether pre-release versions (e.g. "1.2.0-rc.1") are passed to the template
+    ///
+    /// Pre-releases that were folded into their final version by `create-release promote` are
+    /// always available via the `pre_releases` of that version.
+    #[getset(get_copy = "pub")]
+    #[serde(default = "show_pre_releases_default")]
+    show_pre_releases: bool,
+
     /// Whether to edit the data of a changelog entry in the editor
     edit_data: bool,
-    /// Format to edit data in
+    /// Format to write the header of new fragments in, unless given on the commandline
+    #[getset(get_copy = "pub")]
     edit_format: EditFormat,
 
+    /// The pattern for the file names of new fragments, e.g. "{issue}-{subject|slug}"
+    ///
+    /// By default, fragments are named by the time they were created.
+    #[getset(get = "pub")]
+    #[serde(default)]
+    fragment_file_name: Option<crate::file_name::FileNamePattern>,
+
+    /// The editor to edit new fragments with, overriding `core.editor`, VISUAL and EDITOR
+    ///
+    /// Only GIT_EDITOR takes precedence over this setting.
+    #[getset(get = "pub")]
+    #[serde(default)]
+    editor: Option<String>,
+
     /// Set whether and how to use git after creating a new entry
     ///
     /// Possible values are "add" or "commit" (or none, which is default).
@@ -74,8 +110,52 @@ pub struct Configuration {
     #[getset(get_copy = "pub")]
     git_commit_signoff: bool,
 
+    /// Write the version of a new release into the Cargo.toml files of the workspace
+    #[getset(get_copy = "pub")]
+    #[serde(default)]
+    update_manifests: bool,
+
     #[getset(get = "pub")]
     header_fields: IndexMap<String, FragmentDataDesc>,
+
+    /// Rules to infer the bump level from the unreleased fragments with `create-release auto`
+    ///
+    /// If no rule matches, a patch release is created.
+    #[getset(get = "pub")]
+    #[serde(default)]
+    bump_rules: Vec<BumpRule>,
+
+    /// Settings for `check-fragment`, which requires a fragment for changes in a branch
+    #[getset(get = "pub")]
+    #[serde(default)]
+    fragment_gate: FragmentGate,
+
+    /// Settings for `import`, which imports a hand-written changelog into fragments
+    #[getset(get = "pub")]
+    #[serde(default)]
+    import: ImportSettings,
+
+    /// Settings for `from-commits`, which creates fragments from Conventional Commits
+    #[getset(get = "pub")]
+    #[serde(default)]
+    from_commits: FromCommitsSettings,
+
+    /// Settings for committing and tagging a release with `create-release`
+    #[getset(get = "pub")]
+    #[serde(default)]
+    release: ReleaseSettings,
+}
+
+impl Configuration {
+    /// The directory with the fragments of `package`, or the fragment directory itself for `None`
+    ///
+    /// Relative to the repository root, like `fragment_dir()`.
+    pub fn fragment_dir_for(&self, package: Option<&str>) -> PathBuf {
+        match package {
+            None => self.fragment_dir.clone(),
+            Some(package) => self.fragment_dir.join(package),
+        }
+    }
 }
 
 pub fn fragment_dir_default() -> PathBuf {
@@ -90,10 +170,26 @@ pub fn changelog_default() -> PathBuf {
     PathBuf::from("CHANGELOG.md")
 }
 
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub fn show_pre_releases_default() -> bool {
+    true
+}
+
+#[derive(Copy, Clone, Debug, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum EditFormat {
     Toml,
+    Yaml,
+    Json,
+}
+
+impl From<EditFormat> for crate::format::Format {
+    fn from(format: EditFormat) -> Self {
+        match format {
+            EditFormat::Toml => crate::format::Format::Toml,
+            EditFormat::Yaml => crate::format::Format::Yaml,
+            EditFormat::Json => crate::format::Format::Json,
+        }
+    }
 }
 
 impl std::str::FromStr for EditFormat {
@@ -102,28 +198,24 @@ impl std::str::FromStr for EditFormat {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "toml" => Ok(Self::Toml),
+            "yaml" => Ok(Self::Yaml),
+            "json" => Ok(Self::Json),
             fmt => Err(miette::miette!("Unknown edit format {}", fmt)),
         }
     }
 }
 
+/// Find the configuration file in the repository
+pub fn find_config_file(repo_workdir_path: &Path) -> Option<PathBuf> {
+    CONFIG_FILE_NAMES
+        .iter()
+        .map(|config_path| repo_workdir_path.join(config_path))
+        .find(|check_path| check_path.exists())
+}
+
 /// Load the configuration from the repository
 pub fn load(repo_workdir_path: &Path) -> miette::Result<Configuration> {
-    let mut changelog_config_path = None;
-    for config_path in CONFIG_FILE_NAMES {
-        let check_path = {
-            let mut cfg_path = repo_workdir_path.to_path_buf();
-            cfg_path.push(config_path);
-            cfg_path
-        };
-
-        if check_path.exists() {
-            changelog_config_path = Some(check_path);
-            break;
-        }
-    }
-
-    let Some(changelog_config_path) = changelog_config_path else {
+    let Some(changelog_config_path) = find_config_file(repo_workdir_path) else {
         miette::bail!(Error::ConfigDoesNotExist)
     };
 
@@ -136,6 +228,233 @@ pub fn load(repo_workdir_path: &Path) -> miette::Result<Configuration> {
         .into_diagnostic()
 }
 
+/// A rule that requires a certain bump level if a fragment has a certain header value
+#[derive(Debug, getset::Getters, getset::CopyGetters, serde::Deserialize, serde::Serialize)]
+pub struct BumpRule {
+    /// The name of the header field to look at
+    #[getset(get = "pub")]
+    header: String,
+
+    /// The value the header field has to have for the rule to match
+    #[getset(get = "pub")]
+    value: FragmentData,
+
+    /// The bump level required if the rule matches
+    #[getset(get_copy = "pub")]
+    bump: Bump,
+}
+
+/// Exemptions from the requirement to add a fragment with a change, see `check-fragment`
+#[derive(Debug, Default, getset::Getters, serde::Deserialize, serde::Serialize)]
+pub struct FragmentGate {
+    /// Glob patterns of files that can be changed without adding a fragment
+    #[getset(get = "pub")]
+    #[serde(default)]
+    exempt: Vec<String>,
+
+    /// A commit message trailer (e.g. "Changelog: skip") that skips the check if any commit of
+    /// the branch has it
+    #[getset(get = "pub")]
+    skip_trailer: Option<String>,
+
+    /// A file that skips the check if it exists, relative to the repository root
+    ///
+    /// CI can create this file, e.g. if a pull request has a certain label.
+    #[getset(get = "pub")]
+    skip_file: Option<PathBuf>,
+}
+
+/// How `import` maps the entries of a hand-written changelog to header fields
+#[derive(Debug, getset::Getters, serde::Deserialize, serde::Serialize)]
+pub struct ImportSettings {
+    /// The header field for the text of an entry
+    #[getset(get = "pub")]
+    #[serde(default = "import_subject_field_default")]
+    subject_field: String,
+
+    /// The header field for the section (e.g. "Added") of an entry
+    #[getset(get = "pub")]
+    #[serde(default = "import_type_field_default")]
+    type_field: String,
+
+    /// The value of `type_field` for each section (matched ignoring case), sections without a
+    /// value are used as is
+    #[getset(get = "pub")]
+    #[serde(default = "import_sections_default")]
+    sections: IndexMap<String, String>,
+}
+
+impl Default for ImportSettings {
+    fn default() -> Self {
+        Self {
+            subject_field: import_subject_field_default(),
+            type_field: import_type_field_default(),
+            sections: import_sections_default(),
+        }
+    }
+}
+
+fn import_subject_field_default() -> String {
+    "subject".to_string()
+}
+
+fn import_type_field_default() -> String {
+    "type".to_string()
+}
+
+fn import_sections_default() -> IndexMap<String, String> {
+    [
+        ("Added", "Feature"),
+        ("Changed", "Misc"),
+        ("Deprecated", "Misc"),
+        ("Removed", "Misc"),
+        ("Fixed", "Bugfix"),
+        ("Security", "Bugfix"),
+        ("Feature", "Feature"),
+        ("Bugfix", "Bugfix"),
+        ("Doc", "Misc"),
+        ("Removal", "Misc"),
+        ("Misc", "Misc"),
+    ]
+    .into_iter()
+    .map(|(section, value)| (section.to_string(), value.to_string()))
+    .collect()
+}
+
+/// How `create-release` commits and tags a new release
+///
+/// The messages and the tag name are handlebars templates with the `version` and `package` of the
+/// release. The commit message can also use the `tag`, the tag message all fields of the version
+/// that the changelog template has, e.g. its `entries`.
+#[derive(Debug, getset::Getters, getset::CopyGetters, serde::Deserialize, serde::Serialize)]
+pub struct ReleaseSettings {
+    /// Regenerate the changelog, then commit and tag the release
+    ///
+    /// Can also be enabled per call with `create-release --commit`.
+    #[getset(get_copy = "pub")]
+    #[serde(default)]
+    commit: bool,
+
+    /// The message of the release commit
+    #[getset(get = "pub")]
+    #[serde(default = "release_commit_message_default")]
+    commit_message: String,
+
+    /// Whether to create an annotated tag for the release commit
+    #[getset(get_copy = "pub")]
+    #[serde(default = "release_tag_default")]
+    tag: bool,
+
+    /// The name of the tag, e.g. "v1.2.3" or "my-package-v1.2.3"
+    #[getset(get = "pub")]
+    #[serde(default = "release_tag_name_default")]
+    tag_name: String,
+
+    /// The message of the tag, the release notes of the version by default
+    #[getset(get = "pub")]
+    #[serde(default = "release_tag_message_default")]
+    tag_message: String,
+}
+
+impl Default for ReleaseSettings {
+    fn default() -> Self {
+        Self {
+            commit: false,
+            commit_message: release_commit_message_default(),
+            tag: release_tag_default(),
+            tag_name: release_tag_name_default(),
+            tag_message: release_tag_message_default(),
+        }
+    }
+}
+
+fn release_commit_message_default() -> String {
+    "Release {{tag}}".to_string()
+}
+
+fn release_tag_default() -> bool {
+    true
+}
+
+fn release_tag_name_default() -> String {
+    "{{#if package}}{{package}}-{{/if}}v{{version}}".to_string()
+}
+
+fn release_tag_message_default() -> String {
+    "{{#if package}}{{package}} {{/if}}v{{version}}\n\n\
+     {{#each (group_by_header entries \"type\" default=\"Misc\")}}{{@key}}:\n\
+     {{#each this}}- {{this.header.subject}}\n{{/each}}\n{{/each}}"
+        .to_string()
+}
+
+/// How `from-commits` maps Conventional Commits to header fields
+#[derive(Debug, getset::Getters, serde::Deserialize, serde::Serialize)]
+pub struct FromCommitsSettings {
+    /// The header field for the description of a commit
+    #[getset(get = "pub")]
+    #[serde(default = "import_subject_field_default")]
+    subject_field: String,
+
+    /// The header field for the (mapped) type of a commit
+    #[getset(get = "pub")]
+    #[serde(default = "import_type_field_default")]
+    type_field: String,
+
+    /// The header field for the scope of a commit
+    #[getset(get = "pub")]
+    #[serde(default = "from_commits_scope_field_default")]
+    scope_field: String,
+
+    /// The header field that is `true` for breaking changes
+    #[getset(get = "pub")]
+    #[serde(default = "from_commits_breaking_field_default")]
+    breaking_field: String,
+
+    /// The header field for the hash of a commit, which is also used to skip commits that have a
+    /// fragment already
+    #[getset(get = "pub")]
+    #[serde(default = "from_commits_commit_field_default")]
+    commit_field: String,
+
+    /// The value of `type_field` for each commit type (matched ignoring case), commits of other
+    /// types are skipped
+    #[getset(get = "pub")]
+    #[serde(default = "from_commits_types_default")]
+    types: IndexMap<String, String>,
+}
+
+impl Default for FromCommitsSettings {
+    fn default() -> Self {
+        Self {
+            subject_field: import_subject_field_default(),
+            type_field: import_type_field_default(),
+            scope_field: from_commits_scope_field_default(),
+            breaking_field: from_commits_breaking_field_default(),
+            commit_field: from_commits_commit_field_default(),
+            types: from_commits_types_default(),
+        }
+    }
+}
+
+fn from_commits_scope_field_default() -> String {
+    "scope".to_string()
+}
+
+fn from_commits_breaking_field_default() -> String {
+    "breaking".to_string()
+}
+
+fn from_commits_commit_field_default() -> String {
+    "commit".to_string()
+}
+
+fn from_commits_types_default() -> IndexMap<String, String> {
+    [("feat", "Feature"), ("fix", "Bugfix"), ("perf", "Misc")]
+        .into_iter()
+        .map(|(commit_type, value)| (commit_type.to_string(), value.to_string()))
+        .collect()
+}
+
 #[derive(
     Copy, Clone, Debug, Eq, PartialEq, clap::ValueEnum, serde::Deserialize, serde::Serialize,
 )]
@@ -163,6 +482,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_default_config_has_bump_rules() {
+        let config: super::Configuration = toml::from_str(super::DEFAULT_CONFIG).unwrap();
+        assert!(
+            config
+                .bump_rules()
+                .iter()
+                .any(|rule| rule.bump() == crate::version::Bump::Minor),
+            "Default config has no rule for minor bumps: {:?}",
+            config.bump_rules()
+        );
+    }
+
+    #[test]
+    fn test_default_config_has_default_release_settings() {
+        let config: super::Configuration = toml::from_str(super::DEFAULT_CONFIG).unwrap();
+        let defaults = super::ReleaseSettings::default();
+        assert!(!config.release().commit());
+        assert_eq!(config.release().commit_message(), defaults.commit_message());
+        assert_eq!(config.release().tag_name(), defaults.tag_name());
+        assert_eq!(config.release().tag_message(), defaults.tag_message());
+    }
+
     #[test]
     fn test_default_config_has_default_template_path() {
         let config: super::Configuration = toml::from_str(super::DEFAULT_CONFIG).unwrap();
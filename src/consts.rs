@@ -1,5 +1,10 @@
 pub const UNRELEASED_DIR_NAME: &str = "unreleased";
 
+pub const RELEASE_METADATA_FILE_NAME: &str = "metadata.toml";
+
+/// The key of the stable ID of a fragment in its header
+pub const FRAGMENT_ID_KEY: &str = "id";
+
 pub const INTERNAL_TEMPLATE_NAME: &str = "template";
 
 pub const DEFAULT_TEMPLATE: &str = include_str!("../assets/default_template.handlebars.md");
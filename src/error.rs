@@ -1,3 +1,6 @@
+// The miette derive destructures named fields, which trips the unused_assignments lint
+#![allow(unused_assignments)]
+
 use std::path::PathBuf;
 
 #[derive(Debug, thiserror::Error, miette::Diagnostic)]
@@ -14,9 +17,40 @@ pub enum Error {
     #[error("Repository dirty")]
     GitRepoDirty,
 
+    #[error("Cannot stage {}", .path.display())]
+    GitStage {
+        path: PathBuf,
+        #[source]
+        source: git2::Error,
+    },
+
+    #[error("Cannot create the commit")]
+    GitCommit(#[source] git2::Error),
+
+    #[error("The git hook '{hook}' failed with {status}")]
+    GitHookFailed {
+        hook: String,
+        status: std::process::ExitStatus,
+    },
+
+    #[error("Editor exited with {0}, not committing")]
+    CommitEditorFailed(std::process::ExitStatus),
+
+    #[error("Aborting commit due to empty commit message")]
+    CommitMessageEmpty,
+
+    #[error("Not in the worktree of the repository: {}", .0.display())]
+    NotInWorkTree(PathBuf),
+
     #[error("TOML deserialization error")]
     Toml(#[from] toml::de::Error),
 
+    #[error("TOML serialization error")]
+    TomlSer(#[from] toml::ser::Error),
+
+    #[error("TOML editing error")]
+    TomlEdit(#[from] toml_edit::TomlError),
+
     #[error("Time formatting error")]
     TimeFormat(#[from] time::error::Format),
 
@@ -52,8 +86,74 @@ pub enum Error {
     )]
     WorkspaceVersionsNotEqual,
 
-    #[error("EDITOR and VISUAL are not set, cannot find editor")]
-    EditorEnvNotSet,
+    #[error("Release directory exists already: {}", .0.display())]
+    ReleaseDirExists(PathBuf),
+
+    #[error("Release '{0}' does not exist")]
+    ReleaseDoesNotExist(String),
+
+    #[error("Release '{0}' is tagged in git as '{1}' already")]
+    ReleaseTagged(String, String),
+
+    #[error("Tag '{0}' exists already")]
+    TagExists(String),
+
+    #[error("Aborting release due to empty tag message")]
+    #[diagnostic(help("Check the `tag_message` template in the `release` settings"))]
+    TagMessageEmpty,
+
+    #[error("Not a valid name for a git tag: '{0}'")]
+    #[diagnostic(help("Check the `tag_name` pattern in the `release` settings"))]
+    InvalidTagName(String),
+
+    #[error("File exists already: {}", .0.display())]
+    FileExists(PathBuf),
+
+    #[error(
+        "Files changed since '{base}', but no changelog fragment was added:\n{}",
+        .files.iter().map(|f| format!("  {}", f.display())).collect::<Vec<_>>().join("\n")
+    )]
+    #[diagnostic(help(
+        "Add a fragment with `cargo changelog add`, or exempt the change in `fragment_gate`"
+    ))]
+    FragmentMissing { base: String, files: Vec<PathBuf> },
+
+    #[error("Invalid glob pattern")]
+    Glob(#[from] globset::Error),
+
+    #[error("Cannot import from {}: {}", .0.display(), .1)]
+    InvalidImportSource(PathBuf, String),
+
+    #[error("YAML error")]
+    Yaml(#[from] serde_norway::Error),
+
+    #[error("Package '{0}' is not a member of the workspace")]
+    UnknownPackage(String),
+
+    #[error("Version '{0}' is not a pre-release")]
+    NotAPreRelease(String),
+
+    #[error("No editor found, set GIT_EDITOR, `editor` in the configuration, core.editor, VISUAL or EDITOR")]
+    EditorNotSet,
+
+    #[error("Cannot parse editor command: '{0}'")]
+    InvalidEditorCommand(String),
+
+    #[error("Editor exited with {status}, removed {}", .path.display())]
+    EditorFailed {
+        path: PathBuf,
+        status: std::process::ExitStatus,
+    },
+
+    #[error("Edited fragment is invalid, removed {}", .path.display())]
+    FragmentInvalid {
+        path: PathBuf,
+        #[related]
+        errors: Vec<VerificationError>,
+    },
+
+    #[error("Aborted, removed {}", .0.display())]
+    FragmentAborted(PathBuf),
 
     #[error("Environment variable '{0}' is not unicode")]
     EnvNotUnicode(String),
@@ -82,7 +182,7 @@ pub enum FragmentError {
     #[error("IO")]
     Io(#[from] std::io::Error),
 
-    #[error("Expected header seperator: '+++', found: '{0}'")]
+    #[error("Expected header seperator: '+++' or '---', found: '{0}'")]
     ExpectedSeperator(String),
 
     #[error("Header seperator '+++' missing")]
@@ -94,6 +194,12 @@ pub enum FragmentError {
     #[error("TOML deserialization error")]
     TomlDe(#[from] toml::de::Error),
 
+    #[error("YAML error")]
+    Yaml(#[from] serde_norway::Error),
+
+    #[error("JSON error")]
+    Json(#[from] serde_json::Error),
+
     #[error("Type Error: Expected {exp}, got {recv} for field {field_name}")]
     DataType {
         exp: String,
@@ -104,6 +210,19 @@ pub enum FragmentError {
     #[error("Error during interactive session")]
     Interactive(#[from] InteractiveError),
 
+    #[error("Not a valid {exp}: '{value}': {reason}")]
+    InvalidValue {
+        exp: String,
+        value: String,
+        reason: String,
+    },
+
+    #[error("Value of field '{field_name}' violates a constraint: {reason}")]
+    ConstraintViolation { field_name: String, reason: String },
+
+    #[error("Expected a 'key=value' pair, found: '{0}'")]
+    NoKeyValuePair(String),
+
     #[error("Required value '{}', but value is missing", .0)]
     RequiredValueMissing(String),
 
@@ -142,6 +261,98 @@ pub enum VerificationError {
 
     #[error("Error while walking directory")]
     WalkDir(#[from] walkdir::Error),
+
+    #[error("IO error while reading fragment {0}")]
+    Io(PathBuf, #[source] std::io::Error),
+
+    #[error("Invalid header in fragment {path}")]
+    Header {
+        path: PathBuf,
+        #[related]
+        errors: Vec<HeaderError>,
+    },
+}
+
+impl VerificationError {
+    /// The path of the fragment or directory this error is about, if known
+    pub fn path(&self) -> Option<&std::path::Path> {
+        match self {
+            VerificationError::Version(VersionError::Utf8(path)) => Some(path),
+            VerificationError::FragmentParsing(path, _) => Some(path),
+            VerificationError::WalkDir(e) => e.path(),
+            VerificationError::Io(path, _) => Some(path),
+            VerificationError::Header { path, .. } => Some(path),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, miette::Diagnostic)]
+pub enum HeaderError {
+    #[error("Required field '{field}' is missing")]
+    MissingRequiredField {
+        field: String,
+        #[source_code]
+        src: miette::NamedSource<String>,
+        #[label("header without '{field}'")]
+        span: miette::SourceSpan,
+    },
+
+    #[error("Field '{field}' has the wrong type: expected {expected}, got {found}")]
+    TypeMismatch {
+        field: String,
+        expected: String,
+        found: String,
+        #[source_code]
+        src: miette::NamedSource<String>,
+        #[label("expected {expected}")]
+        span: miette::SourceSpan,
+    },
+
+    #[error("Value '{value}' is not allowed for field '{field}'")]
+    #[diagnostic(help("Allowed values: {}", .allowed.join(", ")))]
+    ValueNotAllowed {
+        field: String,
+        value: String,
+        allowed: Vec<String>,
+        #[source_code]
+        src: miette::NamedSource<String>,
+        #[label("not allowed")]
+        span: miette::SourceSpan,
+    },
+
+    #[error("Value '{value}' of field '{field}' is not a valid {expected}")]
+    #[diagnostic(help("{reason}"))]
+    InvalidValue {
+        field: String,
+        value: String,
+        expected: String,
+        reason: String,
+        #[source_code]
+        src: miette::NamedSource<String>,
+        #[label("not a valid {expected}")]
+        span: miette::SourceSpan,
+    },
+
+    #[error("Value of field '{field}' violates a constraint")]
+    #[diagnostic(help("{reason}"))]
+    ConstraintViolation {
+        field: String,
+        reason: String,
+        #[source_code]
+        src: miette::NamedSource<String>,
+        #[label("{reason}")]
+        span: miette::SourceSpan,
+    },
+
+    #[error("Unknown field '{field}'")]
+    #[diagnostic(help("Add the field to `header_fields` in the configuration or remove it"))]
+    UnknownField {
+        field: String,
+        #[source_code]
+        src: miette::NamedSource<String>,
+        #[label("not in the configuration")]
+        span: miette::SourceSpan,
+    },
 }
 
 #[derive(Debug, thiserror::Error, miette::Diagnostic)]
@@ -158,6 +369,9 @@ pub enum InteractiveError {
         crate::fragment::FragmentData,
     ),
 
+    #[error("Failed to parse value")]
+    Parse(#[source] Box<FragmentError>),
+
     #[error("Failed to parse intefer")]
     ParseInt(#[from] std::num::ParseIntError),
 
@@ -0,0 +1,273 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::fragment::FragmentData;
+
+/// The longest slug that `{field|slug}` produces
+const MAX_SLUG_LEN: usize = 50;
+
+/// A pattern for the file names of new fragments, e.g. "{issue}-{subject|slug}"
+///
+/// `{name}` is replaced by the value of the header field `name`, `{name|slug}` by a lowercase
+/// slug of it and `{timestamp}` by the time the fragment was created. The file extension is
+/// added according to the format of the fragment.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct FileNamePattern {
+    source: String,
+    parts: Vec<Part>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Timestamp,
+    Field { name: String, slug: bool },
+}
+
+impl FileNamePattern {
+    /// The file name (without extension) for a fragment with `header`, created at `timestamp`
+    ///
+    /// The values of header fields are restricted to characters that cargo can package. Missing
+    /// fields are left empty, and if nothing is left of the name, it is the `timestamp`.
+    pub fn render(&self, header: &BTreeMap<String, FragmentData>, timestamp: &str) -> String {
+        let name = self
+            .parts
+            .iter()
+            .map(|part| match part {
+                Part::Literal(literal) => literal.clone(),
+                Part::Timestamp => timestamp.to_string(),
+                Part::Field { name, slug } => header
+                    .get(name)
+                    .map(|data| {
+                        let value = data.display().to_string();
+                        if *slug {
+                            slugify(&value)
+                        } else {
+                            sanitize(&value)
+                        }
+                    })
+                    .unwrap_or_default(),
+            })
+            .collect::<String>();
+
+        let name = name.trim_matches(['-', '_', '.', ' ']);
+        if name.is_empty() {
+            timestamp.to_string()
+        } else {
+            name.to_string()
+        }
+    }
+}
+
+impl TryFrom<String> for FileNamePattern {
+    type Error = String;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        if let Some(c) = source.chars().find(|c| matches!(c, '/' | '\\' | ':')) {
+            return Err(format!("File name pattern must not contain '{c}'"));
+        }
+
+        let mut parts = Vec::new();
+        let mut rest = source.as_str();
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                parts.push(literal(&rest[..start])?);
+            }
+
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| format!("Unclosed '{{' in file name pattern '{source}'"))?;
+            let placeholder = &rest[start + 1..start + end];
+            parts.push(match placeholder.split_once('|') {
+                None if placeholder == "timestamp" => Part::Timestamp,
+                None if !placeholder.is_empty() => Part::Field {
+                    name: placeholder.to_string(),
+                    slug: false,
+                },
+                Some((name, "slug")) if !name.is_empty() => Part::Field {
+                    name: name.to_string(),
+                    slug: true,
+                },
+                _ => {
+                    return Err(format!(
+                        "Invalid placeholder '{{{placeholder}}}' in file name pattern, expected '{{field}}', '{{field|slug}}' or '{{timestamp}}'"
+                    ))
+                }
+            });
+            rest = &rest[start + end + 1..];
+        }
+
+        if rest.contains('}') {
+            return Err(format!("Unopened '}}' in file name pattern '{source}'"));
+        }
+        if !rest.is_empty() {
+            parts.push(literal(rest)?);
+        }
+
+        Ok(FileNamePattern { source, parts })
+    }
+}
+
+/// The literal text `s` of a pattern, which may only contain what `sanitize` keeps
+fn literal(s: &str) -> Result<Part, String> {
+    match s.chars().find(|c| !is_allowed(*c)) {
+        Some(c) => Err(format!(
+            "File name pattern must not contain '{c}', only ASCII letters, digits, '-', '_' and '.'"
+        )),
+        None => Ok(Part::Literal(s.to_string())),
+    }
+}
+
+impl From<FileNamePattern> for String {
+    fn from(pattern: FileNamePattern) -> Self {
+        pattern.source
+    }
+}
+
+/// The current time, to name new fragments by
+pub fn timestamp() -> Result<String, crate::error::Error> {
+    // We cannot use the well-known formats here, because cargo cannot package filenames with ":"
+    // in it, but the well-known formats contain this character. Hence we have to use our own.
+    let fragment_file_timestamp_format = time::macros::format_description!(
+        "[year]-[month]-[day]T[hour]_[minute]_[second]_[subsecond]"
+    );
+    Ok(time::OffsetDateTime::now_utc().format(&fragment_file_timestamp_format)?)
+}
+
+/// A path in `dir` for a new `fragment` in `format`, named by the configured
+/// `fragment_file_name` pattern or else by the `timestamp`
+pub fn new_fragment_path(
+    dir: &Path,
+    fragment: &crate::fragment::Fragment,
+    timestamp: &str,
+    format: crate::format::Format,
+    config: &crate::config::Configuration,
+) -> PathBuf {
+    let name = match config.fragment_file_name() {
+        Some(pattern) => pattern.render(fragment.header(), timestamp),
+        None => timestamp.to_string(),
+    };
+    unique_path(dir, &name, format.extension())
+}
+
+/// A path in `dir` for a new file named `name` with `extension` that does not exist yet
+///
+/// If the file exists already, a counter is added to the name, e.g. "name-2.md".
+pub fn unique_path(dir: &Path, name: &str, extension: &str) -> PathBuf {
+    let path = dir.join(format!("{name}.{extension}"));
+    if !path.exists() {
+        return path;
+    }
+
+    (2..)
+        .map(|n| dir.join(format!("{name}-{n}.{extension}")))
+        .find(|path| !path.exists())
+        .unwrap() // the counter is unbounded, so we will find a free name
+}
+
+/// A lowercase version of `s` that only consists of ASCII letters, digits and single dashes
+fn slugify(s: &str) -> String {
+    let slug = s
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+
+    match slug.char_indices().nth(MAX_SLUG_LEN) {
+        Some((idx, _)) => slug[..idx].trim_end_matches('-').to_string(),
+        None => slug,
+    }
+}
+
+/// Whether cargo can package a file name with `c`
+fn is_allowed(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')
+}
+
+/// Replace all characters of `s` that cargo cannot package in a file name
+fn sanitize(s: &str) -> String {
+    s.chars()
+        .map(|c| if is_allowed(c) { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> BTreeMap<String, FragmentData> {
+        BTreeMap::from([
+            ("issue".to_string(), FragmentData::Int(123)),
+            (
+                "subject".to_string(),
+                FragmentData::Str("Fix: the crash on Windows!".to_string()),
+            ),
+        ])
+    }
+
+    fn pattern(s: &str) -> FileNamePattern {
+        FileNamePattern::try_from(s.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            pattern("{issue}-{subject|slug}").render(&header(), "ts"),
+            "123-fix-the-crash-on-windows"
+        );
+        assert_eq!(
+            pattern("{timestamp}_{subject}").render(&header(), "ts"),
+            "ts_Fix__the_crash_on_Windows"
+        );
+    }
+
+    #[test]
+    fn test_render_missing_field() {
+        assert_eq!(
+            pattern("{missing}-{subject|slug}").render(&header(), "ts"),
+            "fix-the-crash-on-windows"
+        );
+        assert_eq!(pattern("{missing}").render(&header(), "ts"), "ts");
+    }
+
+    #[test]
+    fn test_slug_is_limited() {
+        let slug = slugify(&"word ".repeat(20));
+        assert!(slug.len() <= MAX_SLUG_LEN, "Too long: {slug}");
+        assert!(!slug.ends_with('-'));
+    }
+
+    #[test]
+    fn test_invalid_patterns() {
+        for invalid in [
+            "{issue",
+            "issue}",
+            "{}",
+            "{subject|upper}",
+            "a/{issue}",
+            "{a:b}",
+            "{issue} {subject}",
+            "änderung-{issue}",
+            "{issue}*",
+        ] {
+            assert!(
+                FileNamePattern::try_from(invalid.to_string()).is_err(),
+                "Expected error for '{invalid}'"
+            );
+        }
+    }
+
+    #[test]
+    fn test_unique_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = unique_path(dir.path(), "name", "md");
+        assert_eq!(first, dir.path().join("name.md"));
+        std::fs::write(&first, "").unwrap();
+        assert_eq!(
+            unique_path(dir.path(), "name", "md"),
+            dir.path().join("name-2.md")
+        );
+    }
+}
@@ -1,4 +1,52 @@
 #[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
 pub enum Format {
     Toml,
+    Yaml,
+    /// A single JSON document with the header and the text of the fragment
+    Json,
+}
+
+impl Format {
+    /// The line that starts and ends the header of a fragment in this format
+    ///
+    /// JSON fragments have no seperate header, so they have no seperator.
+    pub fn seperator(&self) -> Option<&'static str> {
+        match self {
+            Format::Toml => Some("+++"),
+            Format::Yaml => Some("---"),
+            Format::Json => None,
+        }
+    }
+
+    /// Find the format of a fragment by the first line of its header
+    pub fn from_seperator(line: &str) -> Option<Self> {
+        [Format::Toml, Format::Yaml]
+            .into_iter()
+            .find(|format| format.seperator() == Some(line))
+    }
+
+    /// The file extension of fragment files in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Toml | Format::Yaml => "md",
+            Format::Json => "json",
+        }
+    }
+}
+
+/// Whether `path` has the file extension of a fragment file in any format
+pub fn has_fragment_extension(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            [Format::Toml, Format::Yaml, Format::Json]
+                .iter()
+                .any(|f| f.extension() == ext)
+        })
+}
+
+/// Whether `path` has the file extension of JSON fragment files
+pub fn is_json_file(path: &std::path::Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == Format::Json.extension())
 }
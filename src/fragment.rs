@@ -1,7 +1,9 @@
 use std::collections::BTreeMap;
 use std::io::Read;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
 
 use crate::error::FragmentError;
 use crate::format::Format;
@@ -10,65 +12,115 @@ use crate::format::Format;
     Clone, Debug, getset::Getters, getset::MutGetters, serde::Deserialize, serde::Serialize,
 )]
 pub struct Fragment {
+    /// The ID of the fragment, which stays the same when it is moved by a release or renamed
+    ///
+    /// In TOML and YAML fragments, it is stored in the header.
+    #[getset(get = "pub")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
     #[getset(get = "pub", get_mut = "pub")]
     header: BTreeMap<String, FragmentData>,
     #[getset(get = "pub")]
+    #[serde(default)]
     text: String,
 }
 
 impl Fragment {
     pub fn empty() -> Self {
         Fragment {
+            id: None,
             header: BTreeMap::new(),
             text: String::new(),
         }
     }
 
+    /// Give the fragment a new random ID
+    pub fn generate_id(&mut self) {
+        self.id = Some(format!("{:016x}", fastrand::u64(..)));
+    }
+
     pub fn set_text(&mut self, text: String) {
         self.text = text;
     }
 
+    /// Read a fragment from `reader` in the format of fragment files with the extension of `path`
+    pub fn from_file_reader<R: Read>(path: &Path, reader: &mut R) -> Result<Self, FragmentError> {
+        if crate::format::is_json_file(path) {
+            Self::from_json_reader(reader)
+        } else {
+            Self::from_reader(reader)
+        }
+    }
+
+    /// Read a JSON fragment, a single document with the header and the text
+    pub fn from_json_reader<R: Read>(reader: &mut R) -> Result<Self, FragmentError> {
+        let mut fragment = serde_json::from_reader::<_, Fragment>(reader)?;
+        if fragment.id.is_none() {
+            fragment.id = take_id(&mut fragment.header);
+        }
+        Ok(fragment)
+    }
+
+    /// Read a fragment with a TOML or YAML header between seperator lines, followed by the text
     pub fn from_reader<R: Read>(reader: &mut R) -> Result<Self, FragmentError> {
         let mut buf = String::new();
 
         reader.read_to_string(&mut buf)?;
 
         let mut lines = buf.lines();
-        let format = if let Some(header_sep) = lines.next() {
-            if header_sep == "+++" {
-                Format::Toml
-            } else {
-                return Err(FragmentError::ExpectedSeperator(header_sep.to_string()));
-            }
+        let (format, seperator) = if let Some(header_sep) = lines.next() {
+            let format = Format::from_seperator(header_sep)
+                .ok_or_else(|| FragmentError::ExpectedSeperator(header_sep.to_string()))?;
+            (format, header_sep)
         } else {
             return Err(FragmentError::HeaderSeperatorMissing);
         };
 
-        let header = {
+        let mut header = {
             let header = lines
                 .by_ref()
-                .take_while(|line| *line != "+++")
-                .collect::<Vec<_>>();
+                .take_while(|line| *line != seperator)
+                .collect::<Vec<_>>()
+                .join("\n");
 
             match format {
-                Format::Toml => {
-                    toml::from_str::<BTreeMap<String, FragmentData>>(&header.join("\n"))?
+                Format::Toml => toml::from_str::<BTreeMap<String, FragmentData>>(&header)?,
+                // An empty YAML document is not an empty mapping
+                Format::Yaml if header.trim().is_empty() => BTreeMap::new(),
+                Format::Yaml => serde_norway::from_str::<BTreeMap<String, FragmentData>>(&header)?,
+                Format::Json => {
+                    return Err(FragmentError::ExpectedSeperator(seperator.to_string()))
                 }
             }
         };
 
+        let id = take_id(&mut header);
         let text = lines.collect::<Vec<_>>().join("\n");
 
-        Ok(Fragment { header, text })
+        Ok(Fragment { id, header, text })
     }
 
     pub fn write_to<W: Write>(&self, writer: &mut W, format: Format) -> Result<(), FragmentError> {
-        let (seperator, header) = match format {
-            Format::Toml => {
-                let header = toml::to_string(&self.header)?.trim_end().to_string();
-                ("+++", header)
+        let mut header = self.header.clone();
+        if let Some(id) = self.id.as_ref() {
+            header.insert(
+                crate::consts::FRAGMENT_ID_KEY.to_string(),
+                FragmentData::Str(id.clone()),
+            );
+        }
+
+        let header = match format {
+            Format::Toml => toml::to_string(&header)?,
+            Format::Yaml if header.is_empty() => String::new(),
+            Format::Yaml => serde_norway::to_string(&header)?,
+            Format::Json => {
+                serde_json::to_writer_pretty(&mut *writer, self)?;
+                writeln!(writer)?;
+                return Ok(());
             }
         };
+        let header = header.trim_end();
+        let seperator = format.seperator().unwrap_or_default();
 
         writeln!(writer, "{seperator}")?;
         writeln!(writer, "{header}")?;
@@ -79,16 +131,35 @@ impl Fragment {
 
     #[cfg(test)]
     pub fn new(header: BTreeMap<String, FragmentData>, text: String) -> Self {
-        Self { header, text }
+        Self {
+            id: None,
+            header,
+            text,
+        }
     }
 }
 
-#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+/// Remove the ID of a fragment from its `header`
+///
+/// If the ID is not a string, it is left in the header, where the verification complains about it.
+fn take_id(header: &mut BTreeMap<String, FragmentData>) -> Option<String> {
+    match header.remove(crate::consts::FRAGMENT_ID_KEY)? {
+        FragmentData::Str(id) => Some(id),
+        other => {
+            header.insert(crate::consts::FRAGMENT_ID_KEY.to_string(), other);
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
 #[serde(untagged)]
 pub enum FragmentData {
     Bool(bool),
     Int(u64),
     Str(String),
+    List(Vec<FragmentData>),
+    Map(BTreeMap<String, FragmentData>),
 }
 
 impl FragmentData {
@@ -101,6 +172,8 @@ impl FragmentData {
             FragmentData::Bool(_) => "bool",
             FragmentData::Int(_) => "int",
             FragmentData::Str(_) => "string",
+            FragmentData::List(_) => "list",
+            FragmentData::Map(_) => "map",
         }
     }
 
@@ -119,6 +192,56 @@ impl FragmentData {
             Ok(FragmentData::Str(s.to_string()))
         }
     }
+
+    /// Parse `s` as data of type `ty`
+    ///
+    /// Lists are parsed from comma seperated values, maps from comma seperated "key=value"
+    /// pairs. All other types are parsed like `FragmentData::parse` does.
+    pub fn parse_as(s: &str, ty: &FragmentDataType) -> Result<Self, FragmentError> {
+        match ty {
+            FragmentDataType::Ty(ty) => Self::parse_as_definite(s, ty),
+            FragmentDataType::OneOf(_) => Self::parse(s),
+        }
+    }
+
+    fn parse_as_definite(s: &str, ty: &FragmentDataTypeDefinite) -> Result<Self, FragmentError> {
+        match ty {
+            FragmentDataTypeDefinite::List(element_ty) => split_list(s)
+                .map(|element| Self::parse_as_definite(element, element_ty))
+                .collect::<Result<Vec<_>, _>>()
+                .map(FragmentData::List),
+            FragmentDataTypeDefinite::Map(value_ty) => split_list(s)
+                .map(|pair| {
+                    let (key, value) = pair
+                        .split_once('=')
+                        .ok_or_else(|| FragmentError::NoKeyValuePair(pair.to_string()))?;
+                    let value = Self::parse_as_definite(value.trim(), value_ty)?;
+                    Ok((key.trim().to_string(), value))
+                })
+                .collect::<Result<BTreeMap<_, _>, _>>()
+                .map(FragmentData::Map),
+            FragmentDataTypeDefinite::Str => Ok(FragmentData::Str(s.to_string())),
+            ty @ (FragmentDataTypeDefinite::Date
+            | FragmentDataTypeDefinite::Url
+            | FragmentDataTypeDefinite::Semver) => {
+                ty.validate_str(s)
+                    .map_err(|reason| FragmentError::InvalidValue {
+                        exp: ty.to_string(),
+                        value: s.to_string(),
+                        reason,
+                    })?;
+                Ok(FragmentData::Str(s.to_string()))
+            }
+            FragmentDataTypeDefinite::Bool | FragmentDataTypeDefinite::Int => Self::parse(s),
+        }
+    }
+}
+
+/// Split a comma seperated list, ignoring whitespace around the elements and empty elements
+fn split_list(s: &str) -> impl Iterator<Item = &str> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|element| !element.is_empty())
 }
 
 pub struct FragmentDataDisplay<'a>(&'a FragmentData);
@@ -129,6 +252,17 @@ impl std::fmt::Display for FragmentDataDisplay<'_> {
             FragmentData::Bool(b) => write!(f, "{b}"),
             FragmentData::Int(i) => write!(f, "{i}"),
             FragmentData::Str(s) => write!(f, "{s}"),
+            FragmentData::List(list) => {
+                let list = list.iter().map(|el| el.display().to_string()).join(", ");
+                write!(f, "{list}")
+            }
+            FragmentData::Map(map) => {
+                let map = map
+                    .iter()
+                    .map(|(key, value)| format!("{key}={}", value.display()))
+                    .join(", ");
+                write!(f, "{map}")
+            }
         }
     }
 }
@@ -147,6 +281,117 @@ pub struct FragmentDataDesc {
     required: bool,
     #[getset(get = "pub")]
     crawler: Option<Crawler>,
+
+    /// A regular expression the whole string value has to match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pattern: Option<Pattern>,
+
+    /// The minimum number of characters of a string value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min_length: Option<usize>,
+
+    /// The maximum number of characters of a string value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_length: Option<usize>,
+
+    /// The minimum of an integer value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min: Option<u64>,
+
+    /// The maximum of an integer value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max: Option<u64>,
+}
+
+impl FragmentDataDesc {
+    /// Check `data` against the constraints of this description, returning the violation if any
+    ///
+    /// String constraints apply to strings and to the strings in lists, integer constraints to
+    /// integers and to the integers in lists.
+    pub fn check_constraints(&self, data: &FragmentData) -> Result<(), String> {
+        match data {
+            FragmentData::Str(s) => self.check_str_constraints(s),
+            FragmentData::Int(i) => self.check_int_constraints(*i),
+            FragmentData::List(list) => list
+                .iter()
+                .try_for_each(|element| self.check_constraints(element)),
+            FragmentData::Bool(_) | FragmentData::Map(_) => Ok(()),
+        }
+    }
+
+    fn check_str_constraints(&self, s: &str) -> Result<(), String> {
+        let length = s.chars().count();
+        if let Some(min_length) = self.min_length {
+            if length < min_length {
+                return Err(format!("'{s}' is shorter than {min_length} characters"));
+            }
+        }
+
+        if let Some(max_length) = self.max_length {
+            if length > max_length {
+                return Err(format!("'{s}' is longer than {max_length} characters"));
+            }
+        }
+
+        if let Some(pattern) = self.pattern.as_ref() {
+            if !pattern.is_match(s) {
+                return Err(format!("'{s}' does not match the pattern '{pattern}'"));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_int_constraints(&self, i: u64) -> Result<(), String> {
+        if let Some(min) = self.min {
+            if i < min {
+                return Err(format!("{i} is less than {min}"));
+            }
+        }
+
+        if let Some(max) = self.max {
+            if i > max {
+                return Err(format!("{i} is greater than {max}"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A regular expression that has to match a whole string, e.g. "[A-Z]+-[0-9]+"
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Pattern {
+    source: String,
+    regex: regex::Regex,
+}
+
+impl Pattern {
+    pub fn is_match(&self, s: &str) -> bool {
+        self.regex.is_match(s)
+    }
+}
+
+impl TryFrom<String> for Pattern {
+    type Error = regex::Error;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        let regex = regex::Regex::new(&format!("^(?:{source})$"))?;
+        Ok(Pattern { source, regex })
+    }
+}
+
+impl From<Pattern> for String {
+    fn from(pattern: Pattern) -> Self {
+        pattern.source
+    }
+}
+
+impl std::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
 }
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
@@ -156,22 +401,142 @@ pub enum FragmentDataType {
     OneOf(Vec<String>),
 }
 
-#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+/// A type of header field, written as "bool", "int", "string", "date", "url", "semver",
+/// "list<T>" or "map<T>"
+///
+/// "list" and "map" without element type are lists and maps of strings. Dates, URLs and versions
+/// are strings that are validated, dates have the format "YYYY-MM-DD".
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(try_from = "String", into = "String")]
 pub enum FragmentDataTypeDefinite {
-    #[serde(rename = "bool")]
     Bool,
-    #[serde(rename = "int")]
     Int,
-    #[serde(rename = "string")]
     Str,
+    Date,
+    Url,
+    Semver,
+    List(Box<FragmentDataTypeDefinite>),
+    Map(Box<FragmentDataTypeDefinite>),
+}
+
+impl std::str::FromStr for FragmentDataTypeDefinite {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (outer, element) = match s.split_once('<') {
+            Some((outer, rest)) => {
+                let element = rest
+                    .strip_suffix('>')
+                    .ok_or_else(|| format!("Missing '>' in type '{s}'"))?;
+                (
+                    outer.trim(),
+                    Some(element.parse::<FragmentDataTypeDefinite>()?),
+                )
+            }
+            None => (s, None),
+        };
+
+        match (outer, element) {
+            ("list", element) => Ok(FragmentDataTypeDefinite::List(Box::new(
+                element.unwrap_or(FragmentDataTypeDefinite::Str),
+            ))),
+            ("map", element) => Ok(FragmentDataTypeDefinite::Map(Box::new(
+                element.unwrap_or(FragmentDataTypeDefinite::Str),
+            ))),
+            (_, Some(_)) => Err(format!("Type '{outer}' cannot have an element type")),
+            ("bool", None) => Ok(FragmentDataTypeDefinite::Bool),
+            ("int", None) => Ok(FragmentDataTypeDefinite::Int),
+            ("string", None) => Ok(FragmentDataTypeDefinite::Str),
+            ("date", None) => Ok(FragmentDataTypeDefinite::Date),
+            ("url", None) => Ok(FragmentDataTypeDefinite::Url),
+            ("semver", None) => Ok(FragmentDataTypeDefinite::Semver),
+            (other, None) => Err(format!("Unknown type '{other}'")),
+        }
+    }
+}
+
+impl TryFrom<String> for FragmentDataTypeDefinite {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl std::fmt::Display for FragmentDataTypeDefinite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FragmentDataTypeDefinite::Bool => write!(f, "bool"),
+            FragmentDataTypeDefinite::Int => write!(f, "int"),
+            FragmentDataTypeDefinite::Str => write!(f, "string"),
+            FragmentDataTypeDefinite::Date => write!(f, "date"),
+            FragmentDataTypeDefinite::Url => write!(f, "url"),
+            FragmentDataTypeDefinite::Semver => write!(f, "semver"),
+            FragmentDataTypeDefinite::List(element) => write!(f, "list<{element}>"),
+            FragmentDataTypeDefinite::Map(value) => write!(f, "map<{value}>"),
+        }
+    }
+}
+
+impl From<FragmentDataTypeDefinite> for String {
+    fn from(ty: FragmentDataTypeDefinite) -> Self {
+        ty.to_string()
+    }
+}
+
+impl FragmentDataTypeDefinite {
+    /// Whether this is a type that is stored as a string, but has to have a certain format
+    pub fn is_validated_str(&self) -> bool {
+        matches!(
+            self,
+            FragmentDataTypeDefinite::Date
+                | FragmentDataTypeDefinite::Url
+                | FragmentDataTypeDefinite::Semver
+        )
+    }
+
+    /// Check whether the string `s` is valid for this type, returning the reason if not
+    pub fn validate_str(&self, s: &str) -> Result<(), String> {
+        match self {
+            FragmentDataTypeDefinite::Date => {
+                let date_format = time::macros::format_description!("[year]-[month]-[day]");
+                time::Date::parse(s, &date_format)
+                    .map(|_| ())
+                    .map_err(|e| format!("{e}, expected a date like 2024-01-31"))
+            }
+            FragmentDataTypeDefinite::Url => {
+                url::Url::parse(s).map(|_| ()).map_err(|e| e.to_string())
+            }
+            FragmentDataTypeDefinite::Semver => semver::Version::parse(s)
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            FragmentDataTypeDefinite::Str => Ok(()),
+            other => Err(format!("{other} is not a string type")),
+        }
+    }
+
+    fn matches(&self, data: &FragmentData) -> bool {
+        match (self, data) {
+            (FragmentDataTypeDefinite::Bool, FragmentData::Bool(_)) => true,
+            (FragmentDataTypeDefinite::Int, FragmentData::Int(_)) => true,
+            (FragmentDataTypeDefinite::Str, FragmentData::Str(_)) => true,
+            (ty, FragmentData::Str(s)) if ty.is_validated_str() => ty.validate_str(s).is_ok(),
+            (FragmentDataTypeDefinite::List(element), FragmentData::List(list)) => {
+                list.iter().all(|data| element.matches(data))
+            }
+            (FragmentDataTypeDefinite::Map(value), FragmentData::Map(map)) => {
+                map.values().all(|data| value.matches(data))
+            }
+            (_, _) => false,
+        }
+    }
 }
 
 impl FragmentDataType {
     pub fn type_name(&self) -> String {
         match self {
-            FragmentDataType::Ty(FragmentDataTypeDefinite::Bool) => "bool".to_string(),
-            FragmentDataType::Ty(FragmentDataTypeDefinite::Int) => "int".to_string(),
-            FragmentDataType::Ty(FragmentDataTypeDefinite::Str) => "string".to_string(),
+            FragmentDataType::Ty(ty) => ty.to_string(),
             FragmentDataType::OneOf(list) => {
                 let list = list
                     .iter()
@@ -185,9 +550,7 @@ impl FragmentDataType {
 
     pub fn matches(&self, data: &FragmentData) -> bool {
         match (self, data) {
-            (FragmentDataType::Ty(FragmentDataTypeDefinite::Bool), FragmentData::Bool(_)) => true,
-            (FragmentDataType::Ty(FragmentDataTypeDefinite::Int), FragmentData::Int(_)) => true,
-            (FragmentDataType::Ty(FragmentDataTypeDefinite::Str), FragmentData::Str(_)) => true,
+            (FragmentDataType::Ty(ty), data) => ty.matches(data),
             (FragmentDataType::OneOf(possible_values), FragmentData::Str(s)) => {
                 possible_values.contains(s)
             }
@@ -278,6 +641,280 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_data_type() {
+        use FragmentDataTypeDefinite::*;
+
+        let parse = |s: &str| s.parse::<FragmentDataTypeDefinite>();
+        assert_eq!(parse("int"), Ok(Int));
+        assert_eq!(parse("list"), Ok(List(Box::new(Str))));
+        assert_eq!(parse("list<int>"), Ok(List(Box::new(Int))));
+        assert_eq!(
+            parse("map<list<bool>>"),
+            Ok(Map(Box::new(List(Box::new(Bool)))))
+        );
+        assert!(parse("int<bool>").is_err());
+        assert!(parse("list<int").is_err());
+        assert!(parse("float").is_err());
+        assert_eq!(
+            Map(Box::new(List(Box::new(Int)))).to_string(),
+            "map<list<int>>"
+        );
+    }
+
+    #[test]
+    fn test_validated_string_types() {
+        let ty = |s: &str| FragmentDataType::Ty(s.parse().unwrap());
+        let string = |s: &str| FragmentData::Str(s.to_string());
+
+        assert!(ty("date").matches(&string("2024-02-29")));
+        assert!(!ty("date").matches(&string("2023-02-29")));
+        assert!(!ty("date").matches(&string("yesterday")));
+        assert!(ty("url").matches(&string("https://example.com/docs")));
+        assert!(!ty("url").matches(&string("example.com")));
+        assert!(ty("semver").matches(&string("1.2.3-rc.1")));
+        assert!(!ty("semver").matches(&string("1.2")));
+        assert!(ty("list<date>").matches(&FragmentData::List(vec![string("2024-01-01")])));
+
+        assert!(FragmentData::parse_as("2024-01-01", &ty("date")).is_ok());
+        assert!(matches!(
+            FragmentData::parse_as("01.01.2024", &ty("date")),
+            Err(FragmentError::InvalidValue { .. })
+        ));
+        // A version is not parsed as a number
+        assert_eq!(
+            FragmentData::parse_as("1.2.3", &ty("semver")).unwrap(),
+            string("1.2.3")
+        );
+    }
+
+    #[test]
+    fn test_constraints() {
+        let desc: FragmentDataDesc = toml::from_str(
+            r#"
+            type = "string"
+            required = true
+            pattern = "[A-Z]+-[0-9]+"
+            max_length = 8
+        "#,
+        )
+        .unwrap();
+
+        let string = |s: &str| FragmentData::Str(s.to_string());
+        assert!(desc.check_constraints(&string("ABC-123")).is_ok());
+        assert!(desc.check_constraints(&string("abc-123")).is_err());
+        // The pattern has to match the whole value
+        assert!(desc.check_constraints(&string("see ABC-123")).is_err());
+        assert!(desc.check_constraints(&string("ABCDE-12345")).is_err());
+        assert!(desc
+            .check_constraints(&FragmentData::List(vec![string("A-1"), string("b")]))
+            .is_err());
+
+        let desc: FragmentDataDesc = toml::from_str(
+            r#"
+            type = "int"
+            required = true
+            min = 1
+            max = 10
+        "#,
+        )
+        .unwrap();
+        assert!(desc.check_constraints(&FragmentData::Int(1)).is_ok());
+        assert!(desc.check_constraints(&FragmentData::Int(0)).is_err());
+        assert!(desc.check_constraints(&FragmentData::Int(11)).is_err());
+    }
+
+    #[test]
+    fn test_invalid_pattern_fails_deserialization() {
+        let desc: Result<FragmentDataDesc, _> = toml::from_str(
+            r#"
+            type = "string"
+            required = true
+            pattern = "[A-Z"
+        "#,
+        );
+        assert!(desc.is_err());
+    }
+
+    #[test]
+    fn test_deserializing_data_desc_with_list() {
+        let desc: FragmentDataDesc = toml::from_str(
+            r#"
+            required = false
+            type = "list<int>"
+            default_value = [1, 2]
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(desc.fragment_type().type_name(), "list<int>");
+        let default_value = desc.default_value().as_ref().unwrap();
+        assert!(desc.fragment_type().matches(default_value));
+        assert!(!desc
+            .fragment_type()
+            .matches(&FragmentData::List(vec![FragmentData::Str(
+                "a".to_string()
+            )])));
+    }
+
+    #[test]
+    fn test_parse_as_list_and_map() {
+        let list_ty = FragmentDataType::Ty("list<int>".parse().unwrap());
+        assert_eq!(
+            FragmentData::parse_as("1, 2,3", &list_ty).unwrap(),
+            FragmentData::List(vec![
+                FragmentData::Int(1),
+                FragmentData::Int(2),
+                FragmentData::Int(3)
+            ])
+        );
+
+        let map_ty = FragmentDataType::Ty("map".parse().unwrap());
+        let map = FragmentData::parse_as("a = x, b=y=z", &map_ty).unwrap();
+        assert_eq!(
+            map,
+            FragmentData::Map(BTreeMap::from([
+                ("a".to_string(), FragmentData::Str("x".to_string())),
+                ("b".to_string(), FragmentData::Str("y=z".to_string())),
+            ]))
+        );
+        assert_eq!(map.display().to_string(), "a=x, b=y=z");
+        assert!(FragmentData::parse_as("a", &map_ty).is_err());
+    }
+
+    #[test]
+    fn read_list_and_map_header() {
+        let s = indoc::indoc!(
+            r#"+++
+        authors = ["a", "b"]
+        links = { issue = "https://example.com" }
+        +++
+        "#
+        );
+
+        let f = Fragment::from_reader(&mut Cursor::new(s)).unwrap();
+        assert_eq!(
+            f.header().get("authors"),
+            Some(&FragmentData::List(vec![
+                FragmentData::Str("a".to_string()),
+                FragmentData::Str("b".to_string()),
+            ]))
+        );
+        assert!(matches!(
+            f.header().get("links"),
+            Some(FragmentData::Map(_))
+        ));
+
+        let mut buf = Vec::new();
+        f.write_to(&mut buf, Format::Toml).unwrap();
+        let reread = Fragment::from_reader(&mut Cursor::new(buf)).unwrap();
+        assert_eq!(reread.header(), f.header());
+    }
+
+    #[test]
+    fn read_yaml_header() {
+        let s = indoc::indoc!(
+            r#"---
+        subject: Some subject
+        issue: 123
+        authors:
+          - alice
+        ---
+        This is some text
+        "#
+        );
+
+        let f = Fragment::from_reader(&mut Cursor::new(s)).unwrap();
+        assert_eq!(f.text(), "This is some text");
+        assert_eq!(
+            f.header().get("subject"),
+            Some(&FragmentData::Str("Some subject".to_string()))
+        );
+        assert_eq!(f.header().get("issue"), Some(&FragmentData::Int(123)));
+        assert_eq!(
+            f.header().get("authors"),
+            Some(&FragmentData::List(vec![FragmentData::Str(
+                "alice".to_string()
+            )]))
+        );
+
+        let mut buf = Vec::new();
+        f.write_to(&mut buf, Format::Yaml).unwrap();
+        let written = String::from_utf8(buf).unwrap();
+        assert!(written.starts_with("---\n"), "Not YAML: {written}");
+        let reread = Fragment::from_reader(&mut Cursor::new(written)).unwrap();
+        assert_eq!(reread.header(), f.header());
+        assert_eq!(reread.text(), f.text());
+    }
+
+    #[test]
+    fn read_empty_yaml_header() {
+        let f = Fragment::from_reader(&mut Cursor::new("---\n---\ntext\n")).unwrap();
+        assert!(f.header().is_empty());
+        assert_eq!(f.text(), "text");
+    }
+
+    #[test]
+    fn read_json_fragment() {
+        let s = r#"{"header": {"subject": "Some subject", "issue": 123}, "text": "Some text"}"#;
+
+        let f = Fragment::from_json_reader(&mut Cursor::new(s)).unwrap();
+        assert_eq!(f.text(), "Some text");
+        assert_eq!(
+            f.header().get("subject"),
+            Some(&FragmentData::Str("Some subject".to_string()))
+        );
+        assert_eq!(f.header().get("issue"), Some(&FragmentData::Int(123)));
+
+        let mut buf = Vec::new();
+        f.write_to(&mut buf, Format::Json).unwrap();
+        let reread = Fragment::from_json_reader(&mut Cursor::new(buf)).unwrap();
+        assert_eq!(reread.header(), f.header());
+        assert_eq!(reread.text(), f.text());
+    }
+
+    #[test]
+    fn read_json_fragment_without_text() {
+        let f = Fragment::from_json_reader(&mut Cursor::new(r#"{"header": {}}"#)).unwrap();
+        assert!(f.header().is_empty());
+        assert_eq!(f.text(), "");
+    }
+
+    #[test]
+    fn id_is_kept_out_of_the_header() {
+        let s = "+++\nid = \"abc\"\nsubject = \"foo\"\n+++\ntext\n";
+        let f = Fragment::from_reader(&mut Cursor::new(s)).unwrap();
+        assert_eq!(f.id().as_deref(), Some("abc"));
+        assert!(!f.header().contains_key("id"));
+
+        for format in [Format::Toml, Format::Yaml, Format::Json] {
+            let mut buf = Vec::new();
+            f.write_to(&mut buf, format).unwrap();
+            let path = PathBuf::from(format!("fragment.{}", format.extension()));
+            let reread = Fragment::from_file_reader(&path, &mut Cursor::new(buf)).unwrap();
+            assert_eq!(reread.id(), f.id(), "ID lost in {format:?}");
+            assert_eq!(reread.header(), f.header());
+        }
+    }
+
+    #[test]
+    fn read_format_by_extension() {
+        let json = r#"{"header": {"subject": "foo"}, "text": "text"}"#;
+        let f =
+            Fragment::from_file_reader(Path::new("fragment.json"), &mut Cursor::new(json)).unwrap();
+        assert_eq!(f.text(), "text");
+
+        // A markdown fragment is never read as JSON, even if it looks like it
+        let f = Fragment::from_file_reader(Path::new("fragment.md"), &mut Cursor::new(json));
+        assert!(matches!(f, Err(FragmentError::ExpectedSeperator(_))));
+    }
+
+    #[test]
+    fn read_unknown_seperator() {
+        let f = Fragment::from_reader(&mut Cursor::new("***\n***\n"));
+        assert!(matches!(f, Err(FragmentError::ExpectedSeperator(_))));
+    }
+
     #[test]
     fn test_deserializing_data_desc_with_one_of() {
         let s = r#"
@@ -299,6 +936,11 @@ mod tests {
                     required: false,
                     default_value: None,
                     crawler: None,
+                    pattern: None,
+                    min_length: None,
+                    max_length: None,
+                    min: None,
+                    max: None,
                 };
                 toml::to_string(&fdd).unwrap()
             }
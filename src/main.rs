@@ -13,11 +13,16 @@ mod command;
 mod config;
 mod consts;
 mod error;
+mod file_name;
 mod format;
 mod fragment;
+mod manifest;
+mod release_metadata;
+mod schema;
 mod selector;
 mod template;
 mod util;
+mod version;
 
 use crate::cli::Command;
 use crate::command::Command as _;
@@ -84,36 +89,108 @@ fn main() -> miette::Result<std::process::ExitCode> {
             read,
             set,
             git,
+            package,
         } => crate::command::AddCommand::builder()
+            .repository(repository)
             .interactive(interactive)
             .edit(edit)
             .format(format)
             .text(read)
             .set(set)
             .git(git)
+            .package(package)
             .build()
             .execute(&repo_workdir_path, &config)?,
 
-        Command::VerifyMetadata => crate::command::VerifyMetadataCommand::builder()
+        Command::VerifyMetadata { format } => crate::command::VerifyMetadataCommand::builder()
+            .format(format.unwrap_or_default())
             .build()
             .execute(&repo_workdir_path, &config)?,
 
-        Command::CreateRelease(version) => crate::command::CreateReleaseCommand::builder()
+        Command::CreateRelease {
+            force,
+            update_manifests,
+            commit,
+            build_metadata,
+            package,
+            title,
+            codename,
+            version,
+        } => crate::command::CreateReleaseCommand::builder()
+            .repository(repository)
             .version(version)
+            .title(title)
+            .codename(codename)
+            .build_metadata(build_metadata)
+            .package(package)
+            .force(force)
+            .update_manifests(update_manifests || config.update_manifests())
+            .commit(commit || config.release().commit())
             .build()
             .execute(&repo_workdir_path, &config)?,
 
-        Command::Generate { all, allow_dirty } => {
-            crate::command::GenerateChangelogCommand::builder()
-                .repository(repository)
-                .all(all)
-                .allow_dirty(allow_dirty)
-                .build()
-                .execute(&repo_workdir_path, &config)?
-        }
+        Command::RevertRelease {
+            version,
+            force,
+            package,
+        } => crate::command::RevertReleaseCommand::builder()
+            .repository(repository)
+            .version(version)
+            .force(force)
+            .package(package)
+            .build()
+            .execute(&repo_workdir_path, &config)?,
+
+        Command::Yank {
+            version,
+            undo,
+            package,
+        } => crate::command::YankCommand::builder()
+            .version(version)
+            .undo(undo)
+            .package(package)
+            .build()
+            .execute(&repo_workdir_path, &config)?,
+
+        Command::Import { path, from } => crate::command::ImportCommand::builder()
+            .path(path)
+            .from(from)
+            .build()
+            .execute(&repo_workdir_path, &config)?,
 
-        Command::Show { format, selector } => crate::command::Show::builder()
+        Command::FromCommits { range } => crate::command::FromCommitsCommand::builder()
+            .repository(repository)
+            .range(range)
+            .build()
+            .execute(&repo_workdir_path, &config)?,
+
+        Command::CheckFragment { base } => crate::command::CheckFragmentCommand::builder()
+            .repository(repository)
+            .base(base)
+            .build()
+            .execute(&repo_workdir_path, &config)?,
+
+        Command::Generate {
+            all,
+            allow_dirty,
+            check,
+            package,
+        } => crate::command::GenerateChangelogCommand::builder()
+            .repository(repository)
+            .all(all)
+            .allow_dirty(allow_dirty)
+            .check(check)
+            .package(package)
+            .build()
+            .execute(&repo_workdir_path, &config)?,
+
+        Command::Show {
+            format,
+            package,
+            selector,
+        } => crate::command::Show::builder()
             .format(format)
+            .package(package)
             .selector(selector)
             .build()
             .execute(&repo_workdir_path, &config)?,
@@ -123,8 +200,13 @@ fn main() -> miette::Result<std::process::ExitCode> {
             None
         }
 
-        Command::Has { format, selector } => crate::command::HasCommand::builder()
+        Command::Has {
+            format,
+            package,
+            selector,
+        } => crate::command::HasCommand::builder()
             .format(format)
+            .package(package)
             .selector(selector)
             .build()
             .execute(&repo_workdir_path, &config)?,
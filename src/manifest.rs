@@ -0,0 +1,384 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::error::Error;
+
+const DEPENDENCY_TABLE_NAMES: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Write `version` into the Cargo.toml files of the workspace at `workdir`
+///
+/// This sets the version of all workspace members (or `workspace.package.version` if members
+/// inherit it) and updates the version requirements of dependencies between workspace members.
+///
+/// If `package` is given, only the version of that workspace member and the requirements on it
+/// are updated.
+///
+/// Returns the pathes of the manifests that were changed.
+pub fn update_workspace_versions(
+    workdir: &Path,
+    version: &semver::Version,
+    package: Option<&str>,
+) -> Result<Vec<PathBuf>, Error> {
+    let metadata = cargo_metadata::MetadataCommand::new()
+        .manifest_path(workdir.join("./Cargo.toml"))
+        .no_deps()
+        .exec()?;
+
+    let member_names = metadata
+        .packages
+        .iter()
+        .filter(|pkg| metadata.workspace_members.contains(&pkg.id))
+        .map(|pkg| pkg.name.to_string())
+        .filter(|name| package.is_none_or(|package| package == name))
+        .collect::<BTreeSet<String>>();
+
+    if let Some(package) = package {
+        if member_names.is_empty() {
+            return Err(Error::UnknownPackage(package.to_string()));
+        }
+    }
+
+    let manifest_pathes = std::iter::once(metadata.workspace_root.join("Cargo.toml"))
+        .chain(
+            metadata
+                .packages
+                .iter()
+                .filter(|pkg| metadata.workspace_members.contains(&pkg.id))
+                .map(|pkg| pkg.manifest_path.clone()),
+        )
+        .map(PathBuf::from)
+        .collect::<BTreeSet<PathBuf>>();
+
+    let mut changed = Vec::new();
+    for manifest_path in manifest_pathes {
+        let source = std::fs::read_to_string(&manifest_path)?;
+        let updated = update_manifest_versions(&source, version, &member_names, package.is_none())?;
+
+        if updated != source {
+            tracing::debug!("Updating version in {}", manifest_path.display());
+            std::fs::write(&manifest_path, updated)?;
+            changed.push(manifest_path);
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Update the versions in the manifest `source`, preserving its formatting
+///
+/// Only the version of the packages in `member_names` and the requirements on them are updated.
+/// `workspace.package.version` is only updated if `update_workspace_version` is set.
+fn update_manifest_versions(
+    source: &str,
+    version: &semver::Version,
+    member_names: &BTreeSet<String>,
+    update_workspace_version: bool,
+) -> Result<String, Error> {
+    let mut document = source.parse::<toml_edit::DocumentMut>()?;
+    let version_string = version.to_string();
+
+    let is_updated_member = document
+        .get("package")
+        .and_then(|package| package.get("name"))
+        .and_then(toml_edit::Item::as_str)
+        .is_some_and(|name| member_names.contains(name));
+
+    if let Some(package_version) = document
+        .get_mut("package")
+        .and_then(|package| package.get_mut("version"))
+        .filter(|_| is_updated_member)
+    {
+        if package_version.is_str() {
+            set_string_preserving_decor(package_version, &version_string);
+        } else if !update_workspace_version {
+            tracing::warn!("Package inherits the workspace version, not updating it");
+        }
+    }
+
+    if let Some(workspace) = document.get_mut("workspace") {
+        if let Some(workspace_version) = workspace
+            .get_mut("package")
+            .and_then(|package| package.get_mut("version"))
+            .filter(|_| update_workspace_version)
+        {
+            set_string_preserving_decor(workspace_version, &version_string);
+        }
+
+        if let Some(dependencies) = workspace.get_mut("dependencies") {
+            update_dependency_table(dependencies, version, member_names);
+        }
+    }
+
+    for table_name in DEPENDENCY_TABLE_NAMES {
+        if let Some(dependencies) = document.get_mut(table_name) {
+            update_dependency_table(dependencies, version, member_names);
+        }
+    }
+
+    if let Some(targets) = document
+        .get_mut("target")
+        .and_then(toml_edit::Item::as_table_like_mut)
+    {
+        for (_, target) in targets.iter_mut() {
+            for table_name in DEPENDENCY_TABLE_NAMES {
+                if let Some(dependencies) = target.get_mut(table_name) {
+                    update_dependency_table(dependencies, version, member_names);
+                }
+            }
+        }
+    }
+
+    Ok(document.to_string())
+}
+
+fn update_dependency_table(
+    dependencies: &mut toml_edit::Item,
+    version: &semver::Version,
+    member_names: &BTreeSet<String>,
+) {
+    let Some(dependencies) = dependencies.as_table_like_mut() else {
+        return;
+    };
+
+    for (key, dependency) in dependencies.iter_mut() {
+        let Some(dependency) = dependency.as_table_like_mut() else {
+            // A plain `name = "1.2.3"` dependency cannot be a path dependency, hence it cannot be
+            // a workspace member
+            continue;
+        };
+
+        let package_name = dependency
+            .get("package")
+            .and_then(toml_edit::Item::as_str)
+            .unwrap_or_else(|| key.get())
+            .to_string();
+
+        if !member_names.contains(&package_name) {
+            continue;
+        }
+
+        if let Some(requirement) = dependency.get_mut("version") {
+            if let Some(old) = requirement.as_str() {
+                match update_version_requirement(old, version) {
+                    Some(new) => {
+                        tracing::debug!(
+                            "Updating requirement on '{package_name}': '{old}' -> '{new}'"
+                        );
+                        set_string_preserving_decor(requirement, &new);
+                    }
+                    None => tracing::warn!(
+                        "Not updating requirement '{old}' on '{package_name}' to {version}, update it by hand if necessary"
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// Replace the version in a version requirement, keeping its operator
+///
+/// `"=0.1.0"` becomes `"=0.2.0"` and `"0.1"` becomes `"0.2.0"`. Only requirements with a single
+/// `=`, `^`, `~` or `>=` comparator are updated, for others like `">=1.0, <2.0"`, `"<2.0"` or
+/// `"1.*"` there is no obvious new requirement, so they are `None`.
+fn update_version_requirement(requirement: &str, version: &semver::Version) -> Option<String> {
+    let parsed = semver::VersionReq::parse(requirement).ok()?;
+    let [comparator] = parsed.comparators.as_slice() else {
+        return None;
+    };
+    if !matches!(
+        comparator.op,
+        semver::Op::Exact | semver::Op::Caret | semver::Op::Tilde | semver::Op::GreaterEq
+    ) {
+        return None;
+    }
+
+    let requirement = requirement.trim();
+    let operator_len = requirement
+        .find(|c: char| !matches!(c, '=' | '^' | '~' | '>' | ' '))
+        .unwrap_or(requirement.len());
+
+    Some(format!("{}{}", &requirement[..operator_len], version))
+}
+
+fn set_string_preserving_decor(item: &mut toml_edit::Item, value: &str) {
+    let Some(old_value) = item.as_value() else {
+        return;
+    };
+
+    let mut new_value = toml_edit::Value::from(value);
+    *new_value.decor_mut() = old_value.decor().clone();
+    *item = toml_edit::Item::Value(new_value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members(names: &[&str]) -> BTreeSet<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_update_package_version_preserves_formatting() {
+        let source = indoc::indoc!(
+            r#"
+            [package]
+            name = "foo" # the name
+            version = "0.1.0"   # the version
+
+            [dependencies]
+            serde = "1"
+        "#
+        );
+
+        let updated = update_manifest_versions(
+            source,
+            &semver::Version::new(0, 2, 0),
+            &members(&["foo"]),
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(updated, source.replace("0.1.0", "0.2.0"));
+    }
+
+    #[test]
+    fn test_update_workspace_versions_and_member_dependencies() {
+        let source = indoc::indoc!(
+            r#"
+            [workspace]
+            members = ["foo", "bar"]
+
+            [workspace.package]
+            version = "1.0.0"
+
+            [workspace.dependencies]
+            foo = { path = "foo", version = "=1.0.0" }
+            serde = { version = "1.0.0" }
+
+            [dependencies]
+            bar = { path = "bar", version = "1.0" }
+            baz = { path = "baz", package = "foo", version = "^1.0.0" }
+
+            [target.'cfg(unix)'.dev-dependencies]
+            bar = { path = "bar", version = "1.0.0" }
+        "#
+        );
+
+        let updated = update_manifest_versions(
+            source,
+            &semver::Version::new(1, 1, 0),
+            &members(&["foo", "bar"]),
+            true,
+        )
+        .unwrap();
+
+        let expected = indoc::indoc!(
+            r#"
+            [workspace]
+            members = ["foo", "bar"]
+
+            [workspace.package]
+            version = "1.1.0"
+
+            [workspace.dependencies]
+            foo = { path = "foo", version = "=1.1.0" }
+            serde = { version = "1.0.0" }
+
+            [dependencies]
+            bar = { path = "bar", version = "1.1.0" }
+            baz = { path = "baz", package = "foo", version = "^1.1.0" }
+
+            [target.'cfg(unix)'.dev-dependencies]
+            bar = { path = "bar", version = "1.1.0" }
+        "#
+        );
+
+        assert_eq!(updated, expected);
+    }
+
+    #[test]
+    fn test_update_version_requirement() {
+        let version = semver::Version::new(1, 1, 0);
+        for (old, new) in [
+            ("1.0", Some("1.1.0")),
+            ("=1.0.0", Some("=1.1.0")),
+            ("^1", Some("^1.1.0")),
+            ("~1.0", Some("~1.1.0")),
+            (">= 1.0", Some(">= 1.1.0")),
+            (">=1.0, <2.0", None),
+            ("<2.0", None),
+            ("1.*", None),
+            ("*", None),
+        ] {
+            assert_eq!(
+                update_version_requirement(old, &version).as_deref(),
+                new,
+                "Requirement '{old}'"
+            );
+        }
+    }
+
+    #[test]
+    fn test_inherited_package_version_is_not_touched() {
+        let source = indoc::indoc!(
+            r#"
+            [package]
+            name = "foo"
+            version.workspace = true
+        "#
+        );
+
+        let updated = update_manifest_versions(
+            source,
+            &semver::Version::new(1, 1, 0),
+            &members(&["foo"]),
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(updated, source);
+    }
+
+    #[test]
+    fn test_update_single_package_version() {
+        let source = indoc::indoc!(
+            r#"
+            [workspace.package]
+            version = "1.0.0"
+
+            [package]
+            name = "foo"
+            version = "1.0.0"
+
+            [dependencies]
+            bar = { path = "bar", version = "1.0.0" }
+        "#
+        );
+
+        let updated = update_manifest_versions(
+            source,
+            &semver::Version::new(1, 1, 0),
+            &members(&["bar"]),
+            false,
+        )
+        .unwrap();
+
+        let expected = indoc::indoc!(
+            r#"
+            [workspace.package]
+            version = "1.0.0"
+
+            [package]
+            name = "foo"
+            version = "1.0.0"
+
+            [dependencies]
+            bar = { path = "bar", version = "1.1.0" }
+        "#
+        );
+
+        assert_eq!(updated, expected);
+    }
+}
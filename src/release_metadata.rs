@@ -0,0 +1,151 @@
+use std::path::Path;
+
+use crate::error::Error;
+
+/// Metadata about a release, stored in the directory of the release
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    getset::Getters,
+    getset::CopyGetters,
+    getset::Setters,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+pub struct ReleaseMetadata {
+    /// The date of the release, e.g. "2026-10-18"
+    #[getset(get = "pub")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+
+    /// An optional title for the release
+    #[getset(get = "pub")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+
+    /// An optional codename for the release
+    #[getset(get = "pub")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    codename: Option<String>,
+
+    /// The git commit the release was created on
+    #[getset(get = "pub")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commit: Option<String>,
+
+    /// Whether the release was yanked
+    #[getset(get_copy = "pub", set = "pub")]
+    #[serde(default)]
+    yanked: bool,
+}
+
+impl ReleaseMetadata {
+    pub fn new(
+        date: Option<String>,
+        title: Option<String>,
+        codename: Option<String>,
+        commit: Option<String>,
+    ) -> Self {
+        Self {
+            date,
+            title,
+            codename,
+            commit,
+            yanked: false,
+        }
+    }
+
+    /// Merge the metadata of a release that is created again (with `--force`) into `self`
+    ///
+    /// A new title or codename replaces the existing one, while the date and commit of the
+    /// first release and whether it was yanked are kept.
+    pub fn merge(self, new: ReleaseMetadata) -> Self {
+        Self {
+            date: self.date.or(new.date),
+            title: new.title.or(self.title),
+            codename: new.codename.or(self.codename),
+            commit: self.commit.or(new.commit),
+            yanked: self.yanked,
+        }
+    }
+
+    /// Load the metadata from the directory of a release, if there is any
+    pub fn load(release_dir: &Path) -> Result<Option<Self>, Error> {
+        let path = release_dir.join(crate::consts::RELEASE_METADATA_FILE_NAME);
+        match std::fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content).map(Some).map_err(Error::from),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Error::from(e)),
+        }
+    }
+
+    /// Write the metadata into the directory of a release
+    pub fn write(&self, release_dir: &Path) -> Result<(), Error> {
+        let path = release_dir.join(crate::consts::RELEASE_METADATA_FILE_NAME);
+        std::fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// Today's date in the format used for release dates, e.g. "2026-10-18"
+pub fn today() -> Result<String, Error> {
+    let release_date_format = time::macros::format_description!("[year]-[month]-[day]");
+    time::OffsetDateTime::now_utc()
+        .date()
+        .format(&release_date_format)
+        .map_err(Error::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_metadata_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let metadata = ReleaseMetadata::new(
+            Some("2026-10-18".to_string()),
+            None,
+            Some("Hedgehog".to_string()),
+            None,
+        );
+        metadata.write(dir.path()).unwrap();
+
+        let loaded = ReleaseMetadata::load(dir.path()).unwrap().unwrap();
+        assert_eq!(loaded.date().as_deref(), Some("2026-10-18"));
+        assert_eq!(loaded.title(), &None);
+        assert_eq!(loaded.codename().as_deref(), Some("Hedgehog"));
+        assert!(!loaded.yanked());
+    }
+
+    #[test]
+    fn test_merge_keeps_existing_metadata() {
+        let mut existing = ReleaseMetadata::new(
+            Some("2026-01-02".to_string()),
+            Some("Title".to_string()),
+            Some("Hedgehog".to_string()),
+            Some("abc".to_string()),
+        );
+        existing.set_yanked(true);
+        let new = ReleaseMetadata::new(
+            Some("2026-10-18".to_string()),
+            None,
+            Some("Badger".to_string()),
+            Some("def".to_string()),
+        );
+
+        let merged = existing.merge(new);
+        assert_eq!(merged.date().as_deref(), Some("2026-01-02"));
+        assert_eq!(merged.title().as_deref(), Some("Title"));
+        assert_eq!(merged.codename().as_deref(), Some("Badger"));
+        assert_eq!(merged.commit().as_deref(), Some("abc"));
+        assert!(merged.yanked());
+    }
+
+    #[test]
+    fn test_missing_release_metadata_is_none() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(ReleaseMetadata::load(dir.path()).unwrap().is_none());
+    }
+}
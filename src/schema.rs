@@ -0,0 +1,335 @@
+use std::ops::Range;
+use std::path::Path;
+
+use indexmap::IndexMap;
+
+use crate::error::HeaderError;
+use crate::format::Format;
+use crate::fragment::{Fragment, FragmentDataDesc, FragmentDataType};
+
+/// Validate the header of `fragment` against the configured `header_fields`
+///
+/// `source` is the content of the fragment file at `path`, which is used to point the errors to
+/// the offending parts of the header.
+pub fn validate_header(
+    path: &Path,
+    source: &str,
+    fragment: &Fragment,
+    header_fields: &IndexMap<String, FragmentDataDesc>,
+) -> Vec<HeaderError> {
+    let header = find_header_range(source);
+    let header_range = header.as_ref().map(|(_, range)| range.clone());
+    let header_document = header
+        .as_ref()
+        .filter(|(format, _)| *format == Format::Toml)
+        .and_then(|(_, range)| toml_edit::Document::parse(&source[range.clone()]).ok());
+
+    let named_source = || miette::NamedSource::new(path.display().to_string(), source.to_string());
+
+    // Spans of the key and the value of the header field `name`, relative to `source`
+    let field_spans = |name: &str| -> (miette::SourceSpan, miette::SourceSpan) {
+        let offset = header_range.as_ref().map(|r| r.start).unwrap_or(0);
+        let ranges = match header.as_ref() {
+            Some((Format::Toml, _)) => header_document
+                .as_ref()
+                .and_then(|document| document.as_table().get_key_value(name))
+                .map(|(key, item)| (key.span(), item.span())),
+            Some((Format::Yaml, range)) => yaml_field_ranges(&source[range.clone()], name)
+                .map(|(key, value)| (Some(key), Some(value))),
+            Some((Format::Json, _)) | None => None,
+        };
+        let Some((key, value)) = ranges else {
+            return (
+                whole_header_span(&header_range),
+                whole_header_span(&header_range),
+            );
+        };
+
+        let to_span = |range: Option<Range<usize>>| match range {
+            Some(range) => miette::SourceSpan::from((range.start + offset)..(range.end + offset)),
+            None => whole_header_span(&header_range),
+        };
+        (to_span(key), to_span(value))
+    };
+
+    let mut errors = Vec::new();
+
+    for (name, desc) in header_fields {
+        let Some(data) = fragment.header().get(name) else {
+            if desc.required() {
+                errors.push(HeaderError::MissingRequiredField {
+                    field: name.clone(),
+                    src: named_source(),
+                    span: whole_header_span(&header_range),
+                });
+            }
+            continue;
+        };
+
+        let (_, value_span) = field_spans(name);
+        if desc.fragment_type().matches(data) {
+            if let Err(reason) = desc.check_constraints(data) {
+                errors.push(HeaderError::ConstraintViolation {
+                    field: name.clone(),
+                    reason,
+                    src: named_source(),
+                    span: value_span,
+                });
+            }
+            continue;
+        }
+
+        match (desc.fragment_type(), data) {
+            (FragmentDataType::OneOf(allowed), crate::fragment::FragmentData::Str(value)) => errors
+                .push(HeaderError::ValueNotAllowed {
+                    field: name.clone(),
+                    value: value.clone(),
+                    allowed: allowed.clone(),
+                    src: named_source(),
+                    span: value_span,
+                }),
+            (FragmentDataType::Ty(ty), crate::fragment::FragmentData::Str(value))
+                if ty.is_validated_str() =>
+            {
+                errors.push(HeaderError::InvalidValue {
+                    field: name.clone(),
+                    value: value.clone(),
+                    expected: ty.to_string(),
+                    reason: ty.validate_str(value).err().unwrap_or_default(),
+                    src: named_source(),
+                    span: value_span,
+                })
+            }
+            (expected, found) => errors.push(HeaderError::TypeMismatch {
+                field: name.clone(),
+                expected: expected.type_name(),
+                found: found.type_name().to_string(),
+                src: named_source(),
+                span: value_span,
+            }),
+        }
+    }
+
+    for name in fragment.header().keys() {
+        if !header_fields.contains_key(name) {
+            let (key_span, _) = field_spans(name);
+            errors.push(HeaderError::UnknownField {
+                field: name.clone(),
+                src: named_source(),
+                span: key_span,
+            });
+        }
+    }
+
+    errors
+}
+
+/// Find the format and the byte range of the header in `source`, between the two header seperator
+/// lines
+fn find_header_range(source: &str) -> Option<(Format, Range<usize>)> {
+    let mut offset = 0;
+    let mut start = None;
+
+    for line in source.split_inclusive('\n') {
+        let line_content = line.trim_end_matches(['\r', '\n']);
+        match start {
+            None => match Format::from_seperator(line_content) {
+                Some(format) => start = Some((format, offset + line.len())),
+                None => return None,
+            },
+            Some((format, start)) if format.seperator() == Some(line_content) => {
+                return Some((format, start..offset))
+            }
+            Some(_) => {}
+        }
+        offset += line.len();
+    }
+
+    None
+}
+
+/// Find the byte ranges of the key and the value of the top-level field `name` in a YAML `header`
+///
+/// This only understands the block style that the header is written in by `add`, where each field
+/// starts on a line of its own. A value that does not follow on the line of the key spans the
+/// indented lines below it.
+fn yaml_field_ranges(header: &str, name: &str) -> Option<(Range<usize>, Range<usize>)> {
+    let mut offset = 0;
+    let mut lines = header.split_inclusive('\n');
+
+    for line in lines.by_ref() {
+        let line_start = offset;
+        offset += line.len();
+
+        let Some(rest) = line
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix(':'))
+        else {
+            continue;
+        };
+
+        let key = line_start..line_start + name.len();
+        let value = rest.trim();
+        if !value.is_empty() {
+            let value_start = line_start + name.len() + 1 + (rest.len() - rest.trim_start().len());
+            return Some((key, value_start..value_start + value.len()));
+        }
+
+        let value_start = offset;
+        let mut value_end = offset;
+        for line in lines.by_ref() {
+            if !(line.starts_with(' ') || line.starts_with('-')) {
+                break;
+            }
+            value_end += line.len();
+        }
+        let value_end = header[..value_end].trim_end().len().max(value_start);
+        return Some((key, value_start..value_end));
+    }
+
+    None
+}
+
+/// A span covering the whole header, or the start of the file if there is no header
+fn whole_header_span(header_range: &Option<Range<usize>>) -> miette::SourceSpan {
+    match header_range {
+        Some(range) => miette::SourceSpan::from(range.clone()),
+        None => miette::SourceSpan::from(0..0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn header_fields() -> IndexMap<String, FragmentDataDesc> {
+        toml::from_str(indoc::indoc!(
+            r#"
+            subject = { type = "string", required = true, max_length = 20 }
+            issue = { type = "int", required = false }
+            type = { type = ["Bugfix", "Feature"], required = false }
+            docs_link = { type = "url", required = false }
+        "#
+        ))
+        .unwrap()
+    }
+
+    fn validate(source: &str) -> Vec<HeaderError> {
+        let fragment = Fragment::from_reader(&mut Cursor::new(source)).unwrap();
+        validate_header(
+            Path::new("fragment.md"),
+            source,
+            &fragment,
+            &header_fields(),
+        )
+    }
+
+    #[test]
+    fn test_find_header_range() {
+        let source = "+++\nfoo = 1\n+++\ntext\n";
+        let (format, range) = find_header_range(source).unwrap();
+        assert_eq!(format, Format::Toml);
+        assert_eq!(&source[range], "foo = 1\n");
+        assert_eq!(find_header_range("no header"), None);
+
+        let source = "---\nfoo: 1\n---\ntext\n";
+        let (format, range) = find_header_range(source).unwrap();
+        assert_eq!(format, Format::Yaml);
+        assert_eq!(&source[range], "foo: 1\n");
+    }
+
+    #[test]
+    fn test_valid_header() {
+        let errors = validate("+++\nsubject = \"foo\"\nissue = 1\ntype = \"Bugfix\"\n+++\n");
+        assert!(errors.is_empty(), "Expected no errors: {errors:?}");
+    }
+
+    #[test]
+    fn test_missing_required_field() {
+        let errors = validate("+++\nissue = 1\n+++\n");
+        assert!(
+            matches!(errors.as_slice(), [HeaderError::MissingRequiredField { field, .. }] if field == "subject"),
+            "Unexpected errors: {errors:?}"
+        );
+    }
+
+    #[test]
+    fn test_type_mismatch_points_to_value() {
+        let source = "+++\nsubject = \"foo\"\nissue = \"abc\"\n+++\n";
+        let errors = validate(source);
+        let [HeaderError::TypeMismatch { field, span, .. }] = errors.as_slice() else {
+            panic!("Unexpected errors: {errors:?}");
+        };
+        assert_eq!(field, "issue");
+        assert_eq!(
+            &source[span.offset()..span.offset() + span.len()],
+            "\"abc\""
+        );
+    }
+
+    #[test]
+    fn test_value_not_allowed() {
+        let errors = validate("+++\nsubject = \"foo\"\ntype = \"Other\"\n+++\n");
+        assert!(
+            matches!(errors.as_slice(), [HeaderError::ValueNotAllowed { value, .. }] if value == "Other"),
+            "Unexpected errors: {errors:?}"
+        );
+    }
+
+    #[test]
+    fn test_invalid_url() {
+        let errors = validate("+++\nsubject = \"foo\"\ndocs_link = \"not a url\"\n+++\n");
+        assert!(
+            matches!(errors.as_slice(), [HeaderError::InvalidValue { field, expected, .. }] if field == "docs_link" && expected == "url"),
+            "Unexpected errors: {errors:?}"
+        );
+    }
+
+    #[test]
+    fn test_constraint_violation() {
+        let errors = validate("+++\nsubject = \"This subject is far too long\"\n+++\n");
+        assert!(
+            matches!(errors.as_slice(), [HeaderError::ConstraintViolation { field, .. }] if field == "subject"),
+            "Unexpected errors: {errors:?}"
+        );
+    }
+
+    #[test]
+    fn test_unknown_field_points_to_key() {
+        let source = "+++\nsubject = \"foo\"\nfoo = true\n+++\n";
+        let errors = validate(source);
+        let [HeaderError::UnknownField { field, span, .. }] = errors.as_slice() else {
+            panic!("Unexpected errors: {errors:?}");
+        };
+        assert_eq!(field, "foo");
+        assert_eq!(&source[span.offset()..span.offset() + span.len()], "foo");
+    }
+
+    #[test]
+    fn test_yaml_type_mismatch_points_to_value() {
+        let source = "---\nsubject: foo\nissue: abc\n---\n";
+        let errors = validate(source);
+        let [HeaderError::TypeMismatch { field, span, .. }] = errors.as_slice() else {
+            panic!("Unexpected errors: {errors:?}");
+        };
+        assert_eq!(field, "issue");
+        assert_eq!(&source[span.offset()..span.offset() + span.len()], "abc");
+    }
+
+    #[test]
+    fn test_yaml_unknown_block_field() {
+        let source = "---\nsubject: foo\nfoo:\n  - a\n  - b\n---\n";
+        let errors = validate(source);
+        let [HeaderError::UnknownField { field, span, .. }] = errors.as_slice() else {
+            panic!("Unexpected errors: {errors:?}");
+        };
+        assert_eq!(field, "foo");
+        assert_eq!(&source[span.offset()..span.offset() + span.len()], "foo");
+        assert_eq!(
+            yaml_field_ranges("foo:\n  - a\n  - b\n", "foo").map(|(_, value)| value),
+            Some(5..16)
+        );
+    }
+}
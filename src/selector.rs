@@ -4,11 +4,21 @@ use crate::{cli::Selector, error::Error};
 
 pub struct SelectorExecutor<'sel> {
     selector: Option<&'sel Selector>,
+    package: Option<&'sel str>,
 }
 
 impl<'sel> SelectorExecutor<'sel> {
     pub fn new(selector: Option<&'sel Selector>) -> Self {
-        Self { selector }
+        Self {
+            selector,
+            package: None,
+        }
+    }
+
+    /// Select the fragments of `package` instead of the ones in the fragment directory itself
+    pub fn package(mut self, package: Option<&'sel str>) -> Self {
+        self.package = package;
+        self
     }
 
     pub fn run(
@@ -20,17 +30,19 @@ impl<'sel> SelectorExecutor<'sel> {
             None | Some(Selector::Unreleased) => {
                 tracing::debug!("Showing unreleased");
                 let unreleased_dir_path = workdir
-                    .join(config.fragment_dir())
+                    .join(config.fragment_dir_for(self.package))
                     .join(crate::consts::UNRELEASED_DIR_NAME);
 
                 Self::walk_dir(unreleased_dir_path)
                     .filter_map(Self::result_dir_entry_to_pathbuf)
-                    .filter(|r| !Self::is_gitkeep(r))
+                    .filter(|r| !Self::is_no_fragment(r))
                     .collect::<Result<Vec<PathBuf>, Error>>()
             }
             Some(Selector::Exact { exact }) => {
                 tracing::debug!("Showing exact {exact}");
-                let path = workdir.join(config.fragment_dir()).join(exact);
+                let path = workdir
+                    .join(config.fragment_dir_for(self.package))
+                    .join(exact);
                 if !path.exists() {
                     tracing::warn!("Version does not exist: {exact}");
                     return Ok(vec![]);
@@ -38,7 +50,7 @@ impl<'sel> SelectorExecutor<'sel> {
 
                 Self::walk_dir(path)
                     .filter_map(Self::result_dir_entry_to_pathbuf)
-                    .filter(|r| !Self::is_gitkeep(r))
+                    .filter(|r| !Self::is_no_fragment(r))
                     .collect::<Result<Vec<PathBuf>, Error>>()
             }
             Some(Selector::Range { from, until }) => {
@@ -46,33 +58,42 @@ impl<'sel> SelectorExecutor<'sel> {
                 let from = semver::Version::parse(from)?;
                 let until = semver::Version::parse(until)?;
 
-                let fragment_dir_path = workdir.join(config.fragment_dir());
+                let fragment_dir_path = workdir.join(config.fragment_dir_for(self.package));
 
-                Self::walk_dir(fragment_dir_path)
+                Self::walk_dir(fragment_dir_path.clone())
                     .filter_entry(|de| {
                         tracing::debug!("Looking at {de:?}");
                         if de.path().is_dir() {
                             true
                         } else if de.path().is_file() {
-                            de.path().components().any(|comp| match comp {
-                                std::path::Component::Normal(osstr) => osstr
-                                    .to_str()
-                                    .map(|s| {
-                                        if let Ok(version) = semver::Version::parse(s) {
-                                            version > from && version < until
-                                        } else {
-                                            false
-                                        }
-                                    })
-                                    .unwrap_or(false),
-                                _ => false,
-                            })
+                            // Only the first component is the version, everything else might be
+                            // the directory of a package or of a folded pre-release
+                            let clean_path = de
+                                .path()
+                                .strip_prefix(&fragment_dir_path)
+                                .unwrap_or(de.path());
+                            clean_path
+                                .components()
+                                .next()
+                                .is_some_and(|comp| match comp {
+                                    std::path::Component::Normal(osstr) => osstr
+                                        .to_str()
+                                        .map(|s| {
+                                            if let Ok(version) = semver::Version::parse(s) {
+                                                version > from && version < until
+                                            } else {
+                                                false
+                                            }
+                                        })
+                                        .unwrap_or(false),
+                                    _ => false,
+                                })
                         } else {
                             false
                         }
                     })
                     .filter_map(Self::result_dir_entry_to_pathbuf)
-                    .filter(|r| !Self::is_gitkeep(r))
+                    .filter(|r| !Self::is_no_fragment(r))
                     .collect::<Result<Vec<PathBuf>, Error>>()
             }
         }
@@ -95,9 +116,13 @@ impl<'sel> SelectorExecutor<'sel> {
         }
     }
 
-    fn is_gitkeep(rpath: &Result<PathBuf, Error>) -> bool {
+    fn is_no_fragment(rpath: &Result<PathBuf, Error>) -> bool {
         match rpath {
-            Ok(path) => path.ends_with(".gitkeep"),
+            Ok(path) => {
+                path.ends_with(".gitkeep")
+                    || path.ends_with(crate::consts::RELEASE_METADATA_FILE_NAME)
+                    || !crate::format::has_fragment_extension(path)
+            }
             Err(_) => true,
         }
     }
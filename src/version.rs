@@ -0,0 +1,211 @@
+use crate::error::Error;
+
+/// The level by which a version is bumped
+///
+/// Ordered by significance, so that the highest of multiple bumps can be found via `max()`.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    clap::ValueEnum,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl std::fmt::Display for Bump {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Bump::Patch => write!(f, "patch"),
+            Bump::Minor => write!(f, "minor"),
+            Bump::Major => write!(f, "major"),
+        }
+    }
+}
+
+/// Compute the next version after `current` for the given bump level
+///
+/// A pre-release version is released by a bump that would not go past it, so bumping the minor
+/// version of "1.2.0-rc.1" yields "1.2.0".
+///
+/// Below 1.0.0, a major bump only bumps the minor version, because "0.x" versions communicate
+/// breaking changes via the minor version.
+pub fn bump_version(current: &semver::Version, bump: Bump) -> semver::Version {
+    let mut next = semver::Version::new(current.major, current.minor, current.patch);
+    let is_pre = !current.pre.is_empty();
+
+    let bump = match bump {
+        Bump::Major if current.major == 0 => Bump::Minor,
+        other => other,
+    };
+
+    match bump {
+        Bump::Patch => {
+            if !is_pre {
+                next.patch += 1;
+            }
+        }
+        Bump::Minor => {
+            if !(is_pre && current.patch == 0) {
+                next.minor += 1;
+                next.patch = 0;
+            }
+        }
+        Bump::Major => {
+            if !(is_pre && current.minor == 0 && current.patch == 0) {
+                next.major += 1;
+                next.minor = 0;
+                next.patch = 0;
+            }
+        }
+    }
+
+    next
+}
+
+/// Start a pre-release of the next version, e.g. "1.2.0-rc.1" for a minor bump of "1.1.0"
+pub fn start_pre_release(
+    current: &semver::Version,
+    bump: Bump,
+    identifier: &str,
+) -> Result<semver::Version, Error> {
+    let mut next = bump_version(current, bump);
+    next.pre = semver::Prerelease::new(&format!("{identifier}.1"))?;
+    Ok(next)
+}
+
+/// Increment the pre-release of `current`, e.g. "1.2.0-rc.1" becomes "1.2.0-rc.2"
+///
+/// If `identifier` differs from the one of `current`, the new identifier starts at 1, so
+/// "1.2.0-beta.3" becomes "1.2.0-rc.1" for identifier "rc".
+pub fn next_pre_release(
+    current: &semver::Version,
+    identifier: Option<&str>,
+) -> Result<semver::Version, Error> {
+    if current.pre.is_empty() {
+        return Err(Error::NotAPreRelease(current.to_string()));
+    }
+
+    let (current_identifier, number) = match current.pre.as_str().rsplit_once('.') {
+        Some((ident, number)) => match number.parse::<u64>() {
+            Ok(number) => (Some(ident), Some(number)),
+            Err(_) => (Some(current.pre.as_str()), None),
+        },
+        None => match current.pre.as_str().parse::<u64>() {
+            Ok(number) => (None, Some(number)),
+            Err(_) => (Some(current.pre.as_str()), None),
+        },
+    };
+
+    let pre = match (identifier, current_identifier, number) {
+        (Some(new), Some(old), Some(number)) if new == old => format!("{new}.{}", number + 1),
+        (Some(new), _, _) => format!("{new}.1"),
+        (None, Some(old), Some(number)) => format!("{old}.{}", number + 1),
+        (None, Some(old), None) => format!("{old}.1"),
+        (None, None, Some(number)) => (number + 1).to_string(),
+        (None, None, None) => unreachable!("pre-release is not empty"),
+    };
+
+    let mut next = semver::Version::new(current.major, current.minor, current.patch);
+    next.pre = semver::Prerelease::new(&pre)?;
+    Ok(next)
+}
+
+/// Get the final version for the pre-release `current`, e.g. "1.2.0" for "1.2.0-rc.2"
+pub fn promote_pre_release(current: &semver::Version) -> Result<semver::Version, Error> {
+    if current.pre.is_empty() {
+        return Err(Error::NotAPreRelease(current.to_string()));
+    }
+
+    Ok(semver::Version::new(
+        current.major,
+        current.minor,
+        current.patch,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(s: &str) -> semver::Version {
+        semver::Version::parse(s).unwrap()
+    }
+
+    fn bump(current: &str, bump: Bump) -> String {
+        bump_version(&version(current), bump).to_string()
+    }
+
+    #[test]
+    fn test_bump_stable_versions() {
+        assert_eq!(bump("1.2.3", Bump::Patch), "1.2.4");
+        assert_eq!(bump("1.2.3", Bump::Minor), "1.3.0");
+        assert_eq!(bump("1.2.3", Bump::Major), "2.0.0");
+    }
+
+    #[test]
+    fn test_bump_zero_versions() {
+        assert_eq!(bump("0.1.0", Bump::Patch), "0.1.1");
+        assert_eq!(bump("0.1.0", Bump::Minor), "0.2.0");
+        assert_eq!(bump("0.1.3", Bump::Major), "0.2.0");
+    }
+
+    #[test]
+    fn test_bump_pre_release_versions() {
+        assert_eq!(bump("1.2.3-rc.1", Bump::Patch), "1.2.3");
+        assert_eq!(bump("1.2.0-rc.1", Bump::Minor), "1.2.0");
+        assert_eq!(bump("1.2.3-rc.1", Bump::Minor), "1.3.0");
+        assert_eq!(bump("2.0.0-alpha.1", Bump::Major), "2.0.0");
+        assert_eq!(bump("2.1.0-alpha.1", Bump::Major), "3.0.0");
+    }
+
+    #[test]
+    fn test_bump_drops_build_metadata() {
+        assert_eq!(bump("1.2.3+build.5", Bump::Patch), "1.2.4");
+    }
+
+    #[test]
+    fn test_start_pre_release() {
+        let next = start_pre_release(&version("1.1.0"), Bump::Minor, "rc").unwrap();
+        assert_eq!(next.to_string(), "1.2.0-rc.1");
+
+        let next = start_pre_release(&version("0.3.1"), Bump::Major, "alpha").unwrap();
+        assert_eq!(next.to_string(), "0.4.0-alpha.1");
+    }
+
+    #[test]
+    fn test_next_pre_release() {
+        let next = |current: &str, ident: Option<&str>| {
+            next_pre_release(&version(current), ident)
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(next("1.2.0-rc.1", None), "1.2.0-rc.2");
+        assert_eq!(next("1.2.0-rc.1", Some("rc")), "1.2.0-rc.2");
+        assert_eq!(next("1.2.0-beta.3", Some("rc")), "1.2.0-rc.1");
+        assert_eq!(next("1.2.0-alpha", None), "1.2.0-alpha.1");
+        assert_eq!(next("1.2.0-4", None), "1.2.0-5");
+    }
+
+    #[test]
+    fn test_next_pre_release_fails_for_final_release() {
+        assert!(next_pre_release(&version("1.2.0"), None).is_err());
+    }
+
+    #[test]
+    fn test_promote_pre_release() {
+        let promoted = promote_pre_release(&version("1.2.0-rc.2")).unwrap();
+        assert_eq!(promoted.to_string(), "1.2.0");
+        assert!(promote_pre_release(&version("1.2.0")).is_err());
+    }
+}
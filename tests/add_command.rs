@@ -318,3 +318,281 @@ fn add_command_cannot_create_nonexistent_oneof() {
         .assert()
         .failure();
 }
+
+#[test]
+fn add_command_sets_list_and_map_fields() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    {
+        let config_file_path = temp_dir.path().join("changelog.toml");
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(config_file_path)
+            .unwrap();
+
+        writeln!(file, "[header_fields.authors]").unwrap();
+        writeln!(file, r#"type = "list""#).unwrap();
+        writeln!(file, "required = true").unwrap();
+        writeln!(file, "[header_fields.links]").unwrap();
+        writeln!(file, r#"type = "map<string>""#).unwrap();
+        writeln!(file, "required = false").unwrap();
+        file.sync_all().unwrap()
+    }
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args([
+            "--set",
+            "subject=Some text",
+            "--set",
+            "authors=alice, bob",
+            "--set",
+            "links=issue=https://example.com/1",
+        ])
+        .assert()
+        .success();
+
+    let unreleased_dir = temp_dir.path().join(".changelogs").join("unreleased");
+    let new_fragment_file = std::fs::read_dir(unreleased_dir)
+        .unwrap()
+        .map(Result::unwrap)
+        .find(|de| !de.path().ends_with(".gitkeep"))
+        .unwrap();
+
+    let contents = std::fs::read_to_string(new_fragment_file.path()).unwrap();
+    let toml_header = contents
+        .lines()
+        .skip(1)
+        .take_while(|line| *line != "+++")
+        .collect::<Vec<_>>()
+        .join("\n");
+    let header = toml::from_str::<toml::Table>(&toml_header).unwrap();
+
+    assert_eq!(
+        header["authors"],
+        toml::Value::Array(vec!["alice".into(), "bob".into()])
+    );
+    assert_eq!(
+        header["links"]["issue"].as_str(),
+        Some("https://example.com/1")
+    );
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["verify-metadata"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+}
+
+#[test]
+fn add_command_validates_date_and_url_fields() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    {
+        let config_file_path = temp_dir.path().join("changelog.toml");
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(config_file_path)
+            .unwrap();
+
+        writeln!(file, "[header_fields.deprecated_since]").unwrap();
+        writeln!(file, r#"type = "date""#).unwrap();
+        writeln!(file, "required = false").unwrap();
+        writeln!(file, "[header_fields.docs_link]").unwrap();
+        writeln!(file, r#"type = "url""#).unwrap();
+        writeln!(file, "required = false").unwrap();
+        file.sync_all().unwrap()
+    }
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args([
+            "--set",
+            "subject=Some text",
+            "--set",
+            "deprecated_since=tomorrow",
+        ])
+        .assert()
+        .failure();
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args(["--set", "subject=Some text", "--set", "docs_link=example"])
+        .assert()
+        .failure();
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args([
+            "--set",
+            "subject=Some text",
+            "--set",
+            "deprecated_since=2024-01-31",
+            "--set",
+            "docs_link=https://example.com/docs",
+        ])
+        .assert()
+        .success();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["verify-metadata"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+}
+
+#[test]
+fn add_command_enforces_constraints() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    {
+        let config_file_path = temp_dir.path().join("changelog.toml");
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(config_file_path)
+            .unwrap();
+
+        writeln!(file, "[header_fields.ticket]").unwrap();
+        writeln!(file, r#"type = "string""#).unwrap();
+        writeln!(file, r#"pattern = "[A-Z]+-[0-9]+""#).unwrap();
+        writeln!(file, "required = false").unwrap();
+        file.sync_all().unwrap()
+    }
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args(["--set", "subject=Some text", "--set", "ticket=abc"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("violates a constraint"));
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args(["--set", "subject=Some text", "--set", "ticket=ABC-123"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn add_command_writes_yaml_header_from_config() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    {
+        let config_file_path = temp_dir.path().join("changelog.toml");
+        let config = std::fs::read_to_string(&config_file_path).unwrap();
+        let config = config.replace(r#"edit_format = "toml""#, r#"edit_format = "yaml""#);
+        std::fs::write(&config_file_path, config).unwrap();
+    }
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args([
+            "--set",
+            "issue=123",
+            "--set",
+            "subject=This is some text",
+            "--set",
+            "type=Bugfix",
+        ])
+        .assert()
+        .success();
+
+    let unreleased_dir = temp_dir.path().join(".changelogs").join("unreleased");
+    let new_fragment_file = std::fs::read_dir(unreleased_dir)
+        .unwrap()
+        .map(Result::unwrap)
+        .find(|de| !de.path().ends_with(".gitkeep"))
+        .unwrap();
+
+    let contents = std::fs::read_to_string(new_fragment_file.path()).unwrap();
+    let mut lines = contents.lines();
+    assert_eq!(lines.next(), Some("---"), "Not a YAML header: {contents}");
+    let yaml_header = lines
+        .take_while(|line| *line != "---")
+        .collect::<Vec<_>>()
+        .join("\n");
+    let header = serde_norway::from_str::<serde_norway::Mapping>(&yaml_header).unwrap();
+    assert_eq!(header["issue"].as_u64(), Some(123));
+    assert_eq!(header["subject"].as_str(), Some("This is some text"));
+    assert_eq!(header["type"].as_str(), Some("Bugfix"));
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["verify-metadata"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    // Given on the commandline, the format overrides the configuration
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args(["--format=toml", "--set", "subject=Other text"])
+        .assert()
+        .success();
+
+    let toml_fragments = std::fs::read_dir(temp_dir.path().join(".changelogs/unreleased"))
+        .unwrap()
+        .map(Result::unwrap)
+        .filter(|de| {
+            std::fs::read_to_string(de.path())
+                .unwrap()
+                .starts_with("+++\n")
+        })
+        .count();
+    assert_eq!(toml_fragments, 1);
+}
+
+#[test]
+fn add_command_names_fragment_by_pattern() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    {
+        // Top-level keys have to come before the tables of the configuration
+        let config_file_path = temp_dir.path().join("changelog.toml");
+        let config = std::fs::read_to_string(&config_file_path).unwrap();
+        let config = format!("fragment_file_name = \"{{issue}}-{{subject|slug}}\"\n{config}");
+        std::fs::write(&config_file_path, config).unwrap();
+    }
+
+    for _ in 0..2 {
+        self::common::cargo_changelog_add(temp_dir.path())
+            .args([
+                "--set",
+                "issue=123",
+                "--set",
+                "subject=Fix: the crash on Windows",
+            ])
+            .assert()
+            .success();
+    }
+
+    let unreleased_dir = temp_dir.path().join(".changelogs").join("unreleased");
+    assert!(unreleased_dir
+        .join("123-fix-the-crash-on-windows.md")
+        .is_file());
+    assert!(unreleased_dir
+        .join("123-fix-the-crash-on-windows-2.md")
+        .is_file());
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["verify-metadata"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+}
@@ -7,8 +7,8 @@ mod common;
 // We create a shell script that "edits" a file by creating a file next to it with the same name +
 // ".edited".
 //
-// We set this script as EDITOR and VISUAL and then execute the "add" command. If the test sees the
-// "*.edited" file, it knows that the editor was called
+// We set this script as EDITOR and VISUAL (and make sure GIT_EDITOR is not set) and then execute
+// the "add" command. If the test sees the "*.edited" file, it knows that the editor was called
 //
 
 const EDITOR_COMMAND_SCRIPT: &str = r#"#!/bin/sh
@@ -61,6 +61,7 @@ fn add_command_opens_editor() {
     };
 
     assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .env_remove("GIT_EDITOR")
         .envs([
             ("EDITOR", editor_script_path.display().to_string()),
             ("VISUAL", editor_script_path.display().to_string()),
@@ -104,3 +105,151 @@ fn add_command_opens_editor() {
         files.len()
     );
 }
+
+/// Write an executable script that serves as editor, which gets the fragment path as `$1`
+fn write_editor_script(dir: &std::path::Path, script: &str) -> std::path::PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.join("editor.sh");
+    std::fs::write(&path, format!("#!/bin/sh\n{script}\n")).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+fn unreleased_fragments(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+    std::fs::read_dir(dir.join(".changelogs").join("unreleased"))
+        .unwrap()
+        .map(|de| de.unwrap().path())
+        .filter(|path| !path.ends_with(".gitkeep"))
+        .collect()
+}
+
+#[test]
+fn add_command_keeps_valid_edited_fragment() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+    let editor_dir = tempfile::tempdir().unwrap();
+    let editor = write_editor_script(editor_dir.path(), "echo 'Some text' >> \"$1\"");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args([
+            "add",
+            "--interactive",
+            "false",
+            "--set",
+            "subject=Some subject",
+        ])
+        .env("GIT_EDITOR", editor)
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    let fragments = unreleased_fragments(temp_dir.path());
+    assert_eq!(fragments.len(), 1);
+    let contents = std::fs::read_to_string(&fragments[0]).unwrap();
+    assert!(contents.contains("Some text"), "Not edited: {contents}");
+}
+
+#[test]
+fn add_command_removes_fragment_if_editor_fails() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+    let editor_dir = tempfile::tempdir().unwrap();
+    let editor = write_editor_script(editor_dir.path(), "exit 1");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args([
+            "add",
+            "--interactive",
+            "false",
+            "--set",
+            "subject=Some subject",
+        ])
+        .env("GIT_EDITOR", editor)
+        .current_dir(&temp_dir)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Editor exited with"));
+
+    assert!(unreleased_fragments(temp_dir.path()).is_empty());
+}
+
+#[test]
+fn add_command_removes_invalid_edited_fragment() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+    let editor_dir = tempfile::tempdir().unwrap();
+    // Remove the required subject
+    let editor = write_editor_script(editor_dir.path(), "printf '+++\\n+++\\n' > \"$1\"");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args([
+            "add",
+            "--interactive",
+            "false",
+            "--set",
+            "subject=Some subject",
+        ])
+        .env("GIT_EDITOR", editor)
+        .current_dir(&temp_dir)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Edited fragment is invalid"))
+        .stderr(predicates::str::contains("subject"));
+
+    assert!(unreleased_fragments(temp_dir.path()).is_empty());
+}
+
+#[test]
+fn add_command_uses_editor_with_arguments_from_config() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+    let editor_dir = tempfile::tempdir().unwrap();
+    let editor = write_editor_script(editor_dir.path(), "echo \"Edited with $1\" >> \"$2\"");
+
+    {
+        // Top-level keys have to come before the tables of the configuration
+        let config_file_path = temp_dir.path().join("changelog.toml");
+        let config = std::fs::read_to_string(&config_file_path).unwrap();
+        let config = format!("editor = \"'{}' --wait\"\n{config}", editor.display());
+        std::fs::write(&config_file_path, config).unwrap();
+    }
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args([
+            "add",
+            "--interactive",
+            "false",
+            "--set",
+            "subject=Some subject",
+        ])
+        .env_remove("GIT_EDITOR")
+        .env("EDITOR", "false")
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    let fragments = unreleased_fragments(temp_dir.path());
+    assert_eq!(fragments.len(), 1);
+    let contents = std::fs::read_to_string(&fragments[0]).unwrap();
+    assert!(
+        contents.contains("Edited with --wait"),
+        "Not edited: {contents}"
+    );
+}
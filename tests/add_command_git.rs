@@ -0,0 +1,137 @@
+use std::os::unix::fs::PermissionsExt;
+
+mod common;
+
+fn setup() -> tempfile::TempDir {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::git(temp_dir.path(), &["config", "user.name", "test"]);
+    self::common::git(
+        temp_dir.path(),
+        &["config", "user.email", "test@example.com"],
+    );
+    self::common::init_cargo_changelog(temp_dir.path());
+    self::common::git_commit_all(temp_dir.path(), "Initial commit");
+    temp_dir
+}
+
+fn set_config(dir: &std::path::Path, from: &str, to: &str) {
+    let config_path = dir.join("changelog.toml");
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    assert!(config.contains(from), "'{from}' not in {config}");
+    std::fs::write(config_path, config.replace(from, to)).unwrap();
+}
+
+fn git_output(dir: &std::path::Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn add_entry(dir: &std::path::Path, git: &str) -> assert_cmd::assert::Assert {
+    self::common::cargo_changelog_add(dir)
+        .args(["--format=toml", "--git", git])
+        .args(["--set", "issue=123", "--set", "subject='Subject'"])
+        .args(["--set", "type=Misc"])
+        .assert()
+}
+
+#[test]
+fn add_command_git_add_stages_fragment() {
+    let temp_dir = setup();
+
+    add_entry(temp_dir.path(), "add").success();
+
+    let status = git_output(temp_dir.path(), &["status", "--porcelain"]);
+    assert!(
+        status
+            .lines()
+            .any(|line| line.starts_with("A  .changelogs/unreleased/")),
+        "Fragment not staged: {status}"
+    );
+}
+
+#[test]
+fn add_command_git_commit_only_commits_fragment() {
+    let temp_dir = setup();
+    set_config(
+        temp_dir.path(),
+        "#git_commit_message = \"Create new changelog entry\"",
+        "git_commit_message = \"Add changelog entry\"",
+    );
+    set_config(
+        temp_dir.path(),
+        "git_commit_signoff = false",
+        "git_commit_signoff = true",
+    );
+    std::fs::write(temp_dir.path().join("other.txt"), "other").unwrap();
+    self::common::git(temp_dir.path(), &["add", "other.txt", "changelog.toml"]);
+
+    add_entry(temp_dir.path(), "commit").success();
+
+    let message = git_output(temp_dir.path(), &["log", "-1", "--format=%B"]);
+    assert_eq!(
+        message.trim(),
+        "Add changelog entry\n\nSigned-off-by: test <test@example.com>"
+    );
+    let files = git_output(temp_dir.path(), &["show", "--name-only", "--format="]);
+    assert!(
+        files.trim().starts_with(".changelogs/unreleased/"),
+        "{files}"
+    );
+    assert_eq!(files.trim().lines().count(), 1, "{files}");
+
+    let status = git_output(temp_dir.path(), &["status", "--porcelain"]);
+    assert!(status.contains("A  other.txt"), "{status}");
+}
+
+#[test]
+fn add_command_git_commit_uses_editor_for_message() {
+    let temp_dir = setup();
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .env(
+            "GIT_EDITOR",
+            "sh -c 'echo \"Message from editor\" > \"$1\"' editor",
+        )
+        .args(["--format=toml", "--git", "commit"])
+        .args(["--set", "issue=123", "--set", "subject='Subject'"])
+        .args(["--set", "type=Misc"])
+        .assert()
+        .success();
+
+    let message = git_output(temp_dir.path(), &["log", "-1", "--format=%B"]);
+    assert_eq!(message.trim(), "Message from editor");
+}
+
+#[test]
+fn add_command_git_commit_fails_on_hook() {
+    let temp_dir = setup();
+    set_config(
+        temp_dir.path(),
+        "#git_commit_message = \"Create new changelog entry\"",
+        "git_commit_message = \"Add changelog entry\"",
+    );
+    let hook_path = temp_dir
+        .path()
+        .join(".git")
+        .join("hooks")
+        .join("commit-msg");
+    std::fs::create_dir_all(hook_path.parent().unwrap()).unwrap();
+    std::fs::write(&hook_path, "#!/bin/sh\nexit 1\n").unwrap();
+    std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let head = git_output(temp_dir.path(), &["rev-parse", "HEAD"]);
+
+    add_entry(temp_dir.path(), "commit")
+        .failure()
+        .stderr(predicates::str::contains("commit-msg"));
+
+    assert_eq!(git_output(temp_dir.path(), &["rev-parse", "HEAD"]), head);
+}
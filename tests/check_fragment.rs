@@ -0,0 +1,91 @@
+mod common;
+
+fn init_repo_with_base() -> tempfile::TempDir {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(temp_dir.path(), "check_fragment");
+    self::common::init_cargo_changelog(temp_dir.path());
+    self::common::git_commit_all(temp_dir.path(), "Initial commit");
+    self::common::git(temp_dir.path(), &["tag", "base"]);
+    temp_dir
+}
+
+#[test]
+fn check_fragment_command_fails_without_fragment() {
+    let temp_dir = init_repo_with_base();
+
+    std::fs::write(temp_dir.path().join("src").join("lib.rs"), "// change\n").unwrap();
+    self::common::git_commit_all(temp_dir.path(), "Change source");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["check-fragment", "--base", "base"])
+        .current_dir(&temp_dir)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("src/lib.rs"));
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args(["--set", "subject=Some change", "--set", "type=Misc"])
+        .assert()
+        .success();
+    self::common::git_commit_all(temp_dir.path(), "Add fragment");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["check-fragment", "--base", "base"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+}
+
+#[test]
+fn check_fragment_command_ignores_exempt_files() {
+    let temp_dir = init_repo_with_base();
+
+    std::fs::write(temp_dir.path().join("README.md"), "Some docs\n").unwrap();
+    self::common::git_commit_all(temp_dir.path(), "Change docs");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["check-fragment", "--base", "base"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+}
+
+#[test]
+fn check_fragment_command_is_skipped_by_trailer() {
+    let temp_dir = init_repo_with_base();
+
+    std::fs::write(temp_dir.path().join("src").join("lib.rs"), "// change\n").unwrap();
+    self::common::git_commit_all(temp_dir.path(), "Change source\n\nChangelog: skip\n");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["check-fragment", "--base", "base"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+}
+
+#[test]
+fn check_fragment_command_is_skipped_by_skip_file() {
+    let temp_dir = init_repo_with_base();
+
+    let config_path = temp_dir.path().join("changelog.toml");
+    let config = std::fs::read_to_string(&config_path)
+        .unwrap()
+        .replace("#skip_file", "skip_file");
+    std::fs::write(&config_path, config).unwrap();
+
+    std::fs::write(temp_dir.path().join("src").join("lib.rs"), "// change\n").unwrap();
+    self::common::git_commit_all(temp_dir.path(), "Change source");
+
+    std::fs::write(temp_dir.path().join(".changelog-skip"), "").unwrap();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["check-fragment", "--base", "base"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+}
@@ -12,6 +12,24 @@ pub fn init_git(temp_dir: &std::path::Path) {
     }
 }
 
+pub fn git(temp_dir: &std::path::Path, args: &[&str]) {
+    if !std::process::Command::new("git")
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(temp_dir)
+        .status()
+        .unwrap()
+        .success()
+    {
+        panic!("Failed to git {args:?}");
+    }
+}
+
+pub fn git_commit_all(temp_dir: &std::path::Path, message: &str) {
+    git(temp_dir, &["add", "--all"]);
+    git(temp_dir, &["commit", "--no-verify", "--message", message]);
+}
+
 pub fn init_cargo(temp_dir: &std::path::Path, name: &str) {
     if !std::process::Command::new("cargo")
         .args(["init", "--bin", "--name", name])
@@ -24,6 +42,32 @@ pub fn init_cargo(temp_dir: &std::path::Path, name: &str) {
     }
 }
 
+/// Create a workspace with the given `(name, version)` members, each in its own directory
+pub fn init_cargo_workspace(temp_dir: &std::path::Path, members: &[(&str, &str)]) {
+    let member_list = members
+        .iter()
+        .map(|(name, _)| format!("{name:?}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    std::fs::write(
+        temp_dir.join("Cargo.toml"),
+        format!("[workspace]\nmembers = [{member_list}]\nresolver = \"2\"\n"),
+    )
+    .unwrap();
+
+    for (name, version) in members {
+        let member_dir = temp_dir.join(name);
+        std::fs::create_dir_all(member_dir.join("src")).unwrap();
+        std::fs::write(member_dir.join("src").join("lib.rs"), "").unwrap();
+        std::fs::write(
+            member_dir.join("Cargo.toml"),
+            format!("[package]\nname = \"{name}\"\nversion = \"{version}\"\nedition = \"2021\"\n"),
+        )
+        .unwrap();
+    }
+}
+
 pub fn init_cargo_changelog(temp_dir: &std::path::Path) {
     assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
         .args(["init"])
@@ -27,9 +27,9 @@ fn create_release_command_creates_new_directory() {
         .assert()
         .success();
 
-    let release_dir = temp_dir.path().join(".changelogs").join("0.1.0");
+    let release_dir = temp_dir.path().join(".changelogs").join("0.2.0");
     if !release_dir.exists() {
-        panic!("Release dir '0.1.0' does not exist");
+        panic!("Release dir '0.2.0' does not exist");
     }
 }
 
@@ -77,7 +77,7 @@ fn create_release_command_moves_from_unreleased_dir() {
         );
     }
 
-    let released_dir = temp_dir.path().join(".changelogs").join("0.1.0");
+    let released_dir = temp_dir.path().join(".changelogs").join("0.2.0");
     if released_dir.exists() {
         panic!("Release directory should not exist yet");
     }
@@ -103,10 +103,375 @@ fn create_release_command_moves_from_unreleased_dir() {
         let files = files_in_dir(&released_dir);
         assert_eq!(
             files.len(),
-            1,
-            "Expected 1 entries in released directory, found {}: {:?}",
+            2,
+            "Expected 2 entries (fragment and metadata) in released directory, found {}: {:?}",
             files.len(),
             files
         );
     }
 }
+
+#[test]
+fn create_release_command_bumps_latest_released_version() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(
+        temp_dir.path(),
+        "cargo-changelog-testpkg-create-release_command",
+    );
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    std::fs::create_dir_all(temp_dir.path().join(".changelogs").join("1.4.2")).unwrap();
+    std::fs::create_dir_all(temp_dir.path().join(".changelogs").join("1.3.9")).unwrap();
+
+    for (bump, expected) in [("patch", "1.4.3"), ("minor", "1.5.0"), ("major", "2.0.0")] {
+        assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+            .args(["create-release", bump])
+            .current_dir(&temp_dir)
+            .assert()
+            .success();
+
+        let release_dir = temp_dir.path().join(".changelogs").join(expected);
+        if !release_dir.exists() {
+            panic!("Release dir '{expected}' does not exist after '{bump}' bump");
+        }
+    }
+}
+
+#[test]
+fn create_release_command_refuses_existing_release_dir() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(
+        temp_dir.path(),
+        "cargo-changelog-testpkg-create-release_command",
+    );
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    std::fs::create_dir_all(temp_dir.path().join(".changelogs").join("0.3.0")).unwrap();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "custom", "0.3.0"])
+        .current_dir(&temp_dir)
+        .assert()
+        .failure();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "--force", "custom", "0.3.0"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+}
+
+#[test]
+fn create_release_command_updates_manifests() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+
+    std::fs::write(
+        temp_dir.path().join("Cargo.toml"),
+        indoc::indoc!(
+            r#"
+            [workspace]
+            members = ["foo", "bar"]
+            resolver = "2"
+
+            [workspace.package]
+            version = "0.1.0"
+        "#
+        ),
+    )
+    .unwrap();
+
+    std::fs::create_dir_all(temp_dir.path().join("foo").join("src")).unwrap();
+    std::fs::write(temp_dir.path().join("foo").join("src").join("lib.rs"), "").unwrap();
+    std::fs::write(
+        temp_dir.path().join("foo").join("Cargo.toml"),
+        indoc::indoc!(
+            r#"
+            [package]
+            name = "foo"
+            version.workspace = true
+            edition = "2021"
+        "#
+        ),
+    )
+    .unwrap();
+
+    std::fs::create_dir_all(temp_dir.path().join("bar").join("src")).unwrap();
+    std::fs::write(temp_dir.path().join("bar").join("src").join("lib.rs"), "").unwrap();
+    std::fs::write(
+        temp_dir.path().join("bar").join("Cargo.toml"),
+        indoc::indoc!(
+            r#"
+            [package]
+            name = "bar"
+            version.workspace = true
+            edition = "2021"
+
+            [dependencies]
+            foo = { path = "../foo", version = "=0.1.0" }
+        "#
+        ),
+    )
+    .unwrap();
+
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "--update-manifests", "minor"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    let workspace_manifest = std::fs::read_to_string(temp_dir.path().join("Cargo.toml")).unwrap();
+    assert!(
+        workspace_manifest.contains(r#"version = "0.2.0""#),
+        "Workspace version not updated: {workspace_manifest}"
+    );
+
+    let bar_manifest =
+        std::fs::read_to_string(temp_dir.path().join("bar").join("Cargo.toml")).unwrap();
+    assert!(
+        bar_manifest.contains(r#"foo = { path = "../foo", version = "=0.2.0" }"#),
+        "Dependency requirement not updated: {bar_manifest}"
+    );
+}
+
+#[test]
+fn create_release_command_infers_bump_from_fragments() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(
+        temp_dir.path(),
+        "cargo-changelog-testpkg-create-release_command",
+    );
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args(["--set", "subject=Some fix", "--set", "type=Bugfix"])
+        .assert()
+        .success();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "auto"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("patch release"));
+
+    if !temp_dir.path().join(".changelogs").join("0.1.1").exists() {
+        panic!("Release dir '0.1.1' does not exist");
+    }
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args(["--set", "subject=Some feature", "--set", "type=Feature"])
+        .assert()
+        .success();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "auto"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Creating a minor release"))
+        .stdout(predicates::str::contains("type = Feature"));
+
+    if !temp_dir.path().join(".changelogs").join("0.2.0").exists() {
+        panic!("Release dir '0.2.0' does not exist");
+    }
+}
+
+#[test]
+fn create_release_command_pre_release_workflow() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(
+        temp_dir.path(),
+        "cargo-changelog-testpkg-create-release_command",
+    );
+    self::common::init_cargo_changelog(temp_dir.path());
+    let changelogs_dir = temp_dir.path().join(".changelogs");
+
+    for (subject, args) in [
+        ("First", &["pre-release", "minor", "rc"][..]),
+        ("Second", &["next-pre-release"][..]),
+        ("Third", &["promote"][..]),
+    ] {
+        self::common::cargo_changelog_add(temp_dir.path())
+            .args(["--set", &format!("subject={subject}"), "--set", "type=Misc"])
+            .assert()
+            .success();
+
+        assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+            .arg("create-release")
+            .args(args)
+            .current_dir(&temp_dir)
+            .assert()
+            .success();
+    }
+
+    for dir in ["0.2.0-rc.1", "0.2.0-rc.2"] {
+        if changelogs_dir.join(dir).exists() {
+            panic!("Pre-release dir '{dir}' was not folded into final release");
+        }
+        if !changelogs_dir.join("0.2.0").join(dir).exists() {
+            panic!("Pre-release dir '{dir}' does not exist in final release");
+        }
+    }
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["generate", "--allow-dirty"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    let changelog = std::fs::read_to_string(temp_dir.path().join("CHANGELOG.md")).unwrap();
+    assert!(changelog.contains("## v0.2.0"), "{changelog}");
+    assert!(!changelog.contains("0.2.0-rc"), "{changelog}");
+    for subject in ["First", "Second", "Third"] {
+        assert!(
+            changelog.contains(subject),
+            "{subject} missing: {changelog}"
+        );
+    }
+}
+
+#[test]
+fn create_release_command_folds_pre_releases_into_custom_release() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(
+        temp_dir.path(),
+        "cargo-changelog-testpkg-create-release_command",
+    );
+    self::common::init_cargo_changelog(temp_dir.path());
+    let changelogs_dir = temp_dir.path().join(".changelogs");
+
+    for args in [
+        &["pre-release", "minor", "rc"][..],
+        &["custom", "0.2.0"][..],
+    ] {
+        assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+            .arg("create-release")
+            .args(args)
+            .current_dir(&temp_dir)
+            .assert()
+            .success();
+    }
+
+    if changelogs_dir.join("0.2.0-rc.1").exists() {
+        panic!("Pre-release dir was not folded into final release");
+    }
+    if !changelogs_dir.join("0.2.0").join("0.2.0-rc.1").exists() {
+        panic!("Pre-release dir does not exist in final release");
+    }
+}
+
+#[test]
+fn create_release_command_appends_build_metadata() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(
+        temp_dir.path(),
+        "cargo-changelog-testpkg-create-release_command",
+    );
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "--build-metadata", "build.5", "patch"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    if !temp_dir
+        .path()
+        .join(".changelogs")
+        .join("0.1.1+build.5")
+        .exists()
+    {
+        panic!("Release dir '0.1.1+build.5' does not exist");
+    }
+}
+
+#[test]
+fn create_release_command_writes_release_metadata() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(
+        temp_dir.path(),
+        "cargo-changelog-testpkg-create-release_command",
+    );
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args(["--set", "subject=Some change", "--set", "type=Misc"])
+        .assert()
+        .success();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "--codename", "Hedgehog", "minor"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    let metadata_path = temp_dir
+        .path()
+        .join(".changelogs")
+        .join("0.2.0")
+        .join("metadata.toml");
+    let metadata = std::fs::read_to_string(&metadata_path).unwrap();
+    assert!(metadata.contains("date = "), "{metadata}");
+    assert!(metadata.contains(r#"codename = "Hedgehog""#), "{metadata}");
+    assert!(metadata.contains("yanked = false"), "{metadata}");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["generate", "--allow-dirty"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    let changelog = std::fs::read_to_string(temp_dir.path().join("CHANGELOG.md")).unwrap();
+    assert!(changelog.contains("## v0.2.0 - 2"), "{changelog}");
+
+    // Creating the release again keeps its metadata
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args([
+            "create-release",
+            "--force",
+            "--title",
+            "Title",
+            "custom",
+            "0.2.0",
+        ])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+    let metadata = std::fs::read_to_string(&metadata_path).unwrap();
+    assert!(metadata.contains(r#"codename = "Hedgehog""#), "{metadata}");
+    assert!(metadata.contains(r#"title = "Title""#), "{metadata}");
+}
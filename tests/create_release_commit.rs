@@ -0,0 +1,148 @@
+mod common;
+
+fn setup(name: &str) -> tempfile::TempDir {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::git(temp_dir.path(), &["config", "user.name", "test"]);
+    self::common::git(
+        temp_dir.path(),
+        &["config", "user.email", "test@example.com"],
+    );
+    self::common::init_cargo(temp_dir.path(), name);
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args(["--format=toml"])
+        .args(["--set", "issue=123", "--set", "subject=A new feature"])
+        .args(["--set", "type=Feature"])
+        .assert()
+        .success();
+    temp_dir
+}
+
+fn git_output(dir: &std::path::Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn create_release_command_commits_and_tags_release() {
+    let temp_dir = setup("create_release_commits");
+    self::common::git_commit_all(temp_dir.path(), "Initial commit");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "--commit", "minor"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    let message = git_output(temp_dir.path(), &["log", "-1", "--format=%B"]);
+    assert_eq!(message.trim(), "Release v0.2.0");
+    assert_eq!(
+        git_output(temp_dir.path(), &["status", "--porcelain"]),
+        "",
+        "Release was not committed completely"
+    );
+
+    let files = git_output(temp_dir.path(), &["show", "--name-only", "--format="]);
+    assert!(files.contains("CHANGELOG.md"), "{files}");
+    assert!(files.contains(".changelogs/0.2.0/"), "{files}");
+    let changelog = std::fs::read_to_string(temp_dir.path().join("CHANGELOG.md")).unwrap();
+    assert!(changelog.contains("A new feature"), "{changelog}");
+
+    assert_eq!(
+        git_output(temp_dir.path(), &["cat-file", "-t", "v0.2.0"]).trim(),
+        "tag"
+    );
+    let tag_message = git_output(
+        temp_dir.path(),
+        &["tag", "--list", "--format=%(contents)", "v0.2.0"],
+    );
+    assert!(tag_message.starts_with("v0.2.0\n"), "{tag_message}");
+    assert!(
+        tag_message.contains("Feature:\n- A new feature\n"),
+        "{tag_message}"
+    );
+}
+
+#[test]
+fn create_release_command_refuses_to_commit_dirty_repository() {
+    let temp_dir = setup("create_release_refuses_dirty");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "--commit", "minor"])
+        .current_dir(&temp_dir)
+        .assert()
+        .failure();
+
+    if temp_dir.path().join(".changelogs").join("0.2.0").exists() {
+        panic!("Release directory should not have been created");
+    }
+}
+
+#[test]
+fn create_release_command_uses_tag_name_pattern() {
+    let temp_dir = setup("create_release_tag_name");
+    let config_path = temp_dir.path().join("changelog.toml");
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    let config = config.replace("commit = false", "commit = true").replace(
+        r#"tag_name = "{{#if package}}{{package}}-{{/if}}v{{version}}""#,
+        r#"tag_name = "release-{{version}}""#,
+    );
+    std::fs::write(&config_path, config).unwrap();
+    self::common::git_commit_all(temp_dir.path(), "Initial commit");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "minor"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    let tags = git_output(temp_dir.path(), &["tag", "--list"]);
+    assert_eq!(tags.trim(), "release-0.2.0");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["revert-release", "0.2.0"])
+        .current_dir(&temp_dir)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("release-0.2.0"));
+}
+
+#[test]
+fn create_release_command_keeps_fragments_if_tag_message_is_invalid() {
+    let temp_dir = setup("create_release_invalid_tag_message");
+    let config_path = temp_dir.path().join("changelog.toml");
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    let config = config.replace(
+        r#"tag_message = """"#,
+        r#"tag_message = """{{#if version}}"#,
+    );
+    std::fs::write(&config_path, config).unwrap();
+    self::common::git_commit_all(temp_dir.path(), "Initial commit");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "--commit", "minor"])
+        .current_dir(&temp_dir)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("handlebars template"));
+
+    let changelogs = temp_dir.path().join(".changelogs");
+    assert!(!changelogs.join("0.2.0").exists());
+    let unreleased = std::fs::read_dir(changelogs.join("unreleased"))
+        .unwrap()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name() != ".gitkeep")
+        .count();
+    assert_eq!(unreleased, 1);
+    assert!(git_output(temp_dir.path(), &["tag", "--list"]).is_empty());
+}
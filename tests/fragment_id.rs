@@ -0,0 +1,122 @@
+mod common;
+
+fn show_json(dir: &std::path::Path, selector: &[&str]) -> serde_json::Value {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["show", "--format", "json"])
+        .args(selector)
+        .current_dir(dir)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    serde_json::from_slice(&output).unwrap()
+}
+
+/// The IDs of the fragments in the output of `show --format json`
+fn ids(shown: &serde_json::Value) -> Vec<String> {
+    shown
+        .as_object()
+        .unwrap()
+        .values()
+        .map(|fragment| fragment["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn fragment_id_survives_release() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(temp_dir.path(), "fragment_id_survives_release");
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args(["--set", "subject=Some subject"])
+        .assert()
+        .success();
+
+    let unreleased_ids = ids(&show_json(temp_dir.path(), &["unreleased"]));
+    assert_eq!(unreleased_ids.len(), 1);
+    let id = &unreleased_ids[0];
+    assert!(!id.is_empty());
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["verify-metadata"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "minor"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    assert_eq!(
+        ids(&show_json(temp_dir.path(), &["exact", "0.2.0"])),
+        unreleased_ids
+    );
+
+    let has_output = assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["has", "--format", "json", "exact", "0.2.0"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let has = serde_json::from_slice::<serde_json::Value>(&has_output).unwrap();
+    assert_eq!(has["fragments"][0]["id"].as_str(), Some(id.as_str()));
+
+    std::fs::write(
+        temp_dir.path().join(".changelogs").join("template.md"),
+        "{{#each this.versions}}{{#each this.entries}}{{this.id}}{{/each}}{{/each}}",
+    )
+    .unwrap();
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["generate"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    let changelog = std::fs::read_to_string(temp_dir.path().join("CHANGELOG.md")).unwrap();
+    assert!(changelog.contains(id.as_str()), "Missing ID: {changelog}");
+}
+
+#[test]
+fn has_json_reports_unparseable_fragment_without_id() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+    std::fs::write(
+        temp_dir
+            .path()
+            .join(".changelogs")
+            .join("unreleased")
+            .join("broken.md"),
+        "+++\nsubject = \n+++\n",
+    )
+    .unwrap();
+
+    let has_output = assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["has", "--format", "json", "unreleased"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let has = serde_json::from_slice::<serde_json::Value>(&has_output).unwrap();
+    let fragment = &has["fragments"][0];
+    assert!(
+        fragment["path"].as_str().unwrap().ends_with("broken.md"),
+        "{has}"
+    );
+    assert!(fragment.get("id").is_none(), "{has}");
+}
@@ -0,0 +1,161 @@
+mod common;
+
+fn unreleased_fragments(dir: &std::path::Path) -> Vec<String> {
+    std::fs::read_dir(dir.join(".changelogs").join("unreleased"))
+        .unwrap()
+        .map(|de| de.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .map(|path| std::fs::read_to_string(path).unwrap())
+        .collect()
+}
+
+#[test]
+fn from_commits_creates_fragments_for_conventional_commits() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(temp_dir.path(), "from_commits");
+    self::common::init_cargo_changelog(temp_dir.path());
+    self::common::git_commit_all(temp_dir.path(), "Initial commit");
+    self::common::git(temp_dir.path(), &["tag", "base"]);
+
+    for message in [
+        "feat(parser)!: Support comments\n\nComments start with '#'.",
+        "chore: Update dependencies",
+        "fix: Crash on empty input",
+        "Not a conventional commit",
+    ] {
+        self::common::git(temp_dir.path(), &["commit", "--allow-empty", "-m", message]);
+    }
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["from-commits", "base..HEAD"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Created 2 fragments, skipped 2 commits",
+        ));
+
+    let fragments = unreleased_fragments(temp_dir.path());
+    assert_eq!(fragments.len(), 2, "{fragments:?}");
+    let feature = fragments
+        .iter()
+        .find(|fragment| fragment.contains("Support comments"))
+        .unwrap();
+    assert!(feature.contains(r#"type = "Feature""#), "{feature}");
+    assert!(feature.contains(r#"scope = "parser""#), "{feature}");
+    assert!(feature.contains("breaking = true"), "{feature}");
+    assert!(feature.contains("commit = "), "{feature}");
+    assert!(feature.contains("Comments start with '#'."), "{feature}");
+    let fix = fragments
+        .iter()
+        .find(|fragment| fragment.contains("Crash on empty input"))
+        .unwrap();
+    assert!(fix.contains(r#"type = "Bugfix""#), "{fix}");
+    assert!(!fix.contains("breaking"), "{fix}");
+
+    let config = std::fs::read_to_string(temp_dir.path().join("changelog.toml")).unwrap();
+    assert!(config.contains("commit = {"), "{config}");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["verify-metadata"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+}
+
+#[test]
+fn from_commits_skips_commits_with_fragments() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(temp_dir.path(), "from_commits");
+    self::common::init_cargo_changelog(temp_dir.path());
+    self::common::git_commit_all(temp_dir.path(), "Initial commit");
+    self::common::git(temp_dir.path(), &["tag", "base"]);
+    self::common::git(
+        temp_dir.path(),
+        &["commit", "--allow-empty", "-m", "feat: A feature"],
+    );
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["from-commits", "base..HEAD"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Created 1 fragments"));
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["from-commits", "base..HEAD"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Created 0 fragments, skipped 1 commits",
+        ));
+    assert_eq!(unreleased_fragments(temp_dir.path()).len(), 1);
+}
+
+#[test]
+fn from_commits_matches_types_ignoring_case_and_counts_merges() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(temp_dir.path(), "from_commits");
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    // Without a type field, the added one must not invalidate existing fragments
+    let config_path = temp_dir.path().join("changelog.toml");
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    let type_field =
+        "[header_fields.type]\ntype = [ \"Bugfix\", \"Feature\", \"Misc\" ]\nrequired = false\n";
+    assert!(config.contains(type_field), "{config}");
+    std::fs::write(&config_path, config.replace(type_field, "")).unwrap();
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args(["--set", "subject=Without a type"])
+        .assert()
+        .success();
+
+    self::common::git_commit_all(temp_dir.path(), "Initial commit");
+    self::common::git(temp_dir.path(), &["tag", "base"]);
+    self::common::git(temp_dir.path(), &["checkout", "-q", "-b", "topic"]);
+    self::common::git(
+        temp_dir.path(),
+        &["commit", "--allow-empty", "-m", "FIX: Crash on empty input"],
+    );
+    self::common::git(temp_dir.path(), &["checkout", "-q", "-"]);
+    self::common::git(
+        temp_dir.path(),
+        &["commit", "--allow-empty", "-m", "Feat: Support comments"],
+    );
+    self::common::git(
+        temp_dir.path(),
+        &["merge", "--no-ff", "-m", "Merge branch 'topic'", "topic"],
+    );
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["from-commits", "base..HEAD"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Created 2 fragments, skipped 1 commits",
+        ));
+
+    let fragments = unreleased_fragments(temp_dir.path()).join("\n");
+    assert!(fragments.contains(r#"type = "Bugfix""#), "{fragments}");
+    assert!(fragments.contains(r#"type = "Feature""#), "{fragments}");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["verify-metadata"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+}
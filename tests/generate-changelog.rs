@@ -344,3 +344,128 @@ fn generate_changelog_works_with_default_headers() {
         panic!("Changelog is not a file");
     }
 }
+
+#[test]
+fn generate_changelog_command_check_detects_outdated_changelog() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(temp_dir.path(), "generate_changelog_command_check");
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args(["--set", "subject='First subject'", "--set", "type=Misc"])
+        .assert()
+        .success();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "minor"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["generate"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["generate", "--check"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout("");
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args(["--set", "subject='Second subject'", "--set", "type=Misc"])
+        .assert()
+        .success();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "patch"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    let changelog_before = std::fs::read_to_string(temp_dir.path().join("CHANGELOG.md")).unwrap();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["generate", "--check"])
+        .current_dir(&temp_dir)
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("--- a/CHANGELOG.md"))
+        .stdout(predicates::str::contains("+## v0.2.1"))
+        .stdout(predicates::str::contains("Second subject"));
+
+    let changelog_after = std::fs::read_to_string(temp_dir.path().join("CHANGELOG.md")).unwrap();
+    assert_eq!(changelog_before, changelog_after, "Check must not write");
+}
+
+#[test]
+fn generate_changelog_command_includes_json_fragments() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(temp_dir.path(), "generate_changelog_json");
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args([
+            "--format=json",
+            "--set",
+            "subject=Written by add",
+            "--set",
+            "type=Misc",
+        ])
+        .assert()
+        .success();
+
+    let unreleased_dir = temp_dir.path().join(".changelogs").join("unreleased");
+    let added = std::fs::read_dir(&unreleased_dir)
+        .unwrap()
+        .map(Result::unwrap)
+        .find(|de| !de.path().ends_with(".gitkeep"))
+        .unwrap()
+        .path();
+    assert_eq!(added.extension().and_then(|e| e.to_str()), Some("json"));
+
+    std::fs::write(
+        unreleased_dir.join("from-bot.json"),
+        r#"{"header": {"subject": "Written by a bot", "type": "Misc"}, "text": "Bot text"}"#,
+    )
+    .unwrap();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["verify-metadata"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "minor"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["generate"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    let changelog = std::fs::read_to_string(temp_dir.path().join("CHANGELOG.md")).unwrap();
+    assert!(
+        changelog.contains("Written by add"),
+        "Missing fragment: {changelog}"
+    );
+    assert!(
+        changelog.contains("Written by a bot"),
+        "Missing fragment: {changelog}"
+    );
+}
@@ -0,0 +1,402 @@
+mod common;
+
+const CHANGELOG: &str = r#"# Changelog
+
+## [Unreleased]
+### Fixed
+- A fix that is not released yet
+
+## [0.2.0] - 2026-10-18
+### Added
+- A new feature
+  with a longer description
+### Removed
+- An old feature
+
+## [0.1.0] - 2026-01-02
+### Added
+- The first feature
+
+[0.2.0]: https://example.com/compare/v0.1.0...v0.2.0
+"#;
+
+#[test]
+fn import_command_creates_fragments_per_version() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(temp_dir.path(), "import_command");
+    self::common::init_cargo_changelog(temp_dir.path());
+    std::fs::write(temp_dir.path().join("OLD_CHANGELOG.md"), CHANGELOG).unwrap();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["import", "OLD_CHANGELOG.md"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Imported 4 entries"));
+
+    let fragment_dir = temp_dir.path().join(".changelogs");
+    let metadata =
+        std::fs::read_to_string(fragment_dir.join("0.2.0").join("metadata.toml")).unwrap();
+    assert!(metadata.contains("2026-10-18"), "No date: {metadata}");
+
+    let fragments_in = |dir: &str| {
+        std::fs::read_dir(fragment_dir.join(dir))
+            .unwrap()
+            .map(|de| de.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+            .map(|path| std::fs::read_to_string(path).unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(fragments_in("0.1.0").len(), 1);
+    assert_eq!(fragments_in("unreleased").len(), 1);
+    assert!(fragments_in("unreleased")[0].contains(r#"type = "Bugfix""#));
+
+    let released = fragments_in("0.2.0").join("\n");
+    assert!(
+        released.contains(r#"subject = "A new feature""#),
+        "{released}"
+    );
+    assert!(released.contains("with a longer description"), "{released}");
+    assert!(released.contains(r#"type = "Feature""#), "{released}");
+    assert!(released.contains(r#"type = "Misc""#), "{released}");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["verify-metadata"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["generate"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+    let changelog = std::fs::read_to_string(temp_dir.path().join("CHANGELOG.md")).unwrap();
+    assert!(changelog.contains("The first feature"), "{changelog}");
+}
+
+#[test]
+fn import_command_reports_unmapped_lines() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+    std::fs::write(
+        temp_dir.path().join("OLD_CHANGELOG.md"),
+        "## 0.1.0\n### Improved\n- Something\n\nA paragraph\n",
+    )
+    .unwrap();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["import", "OLD_CHANGELOG.md"])
+        .current_dir(&temp_dir)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "OLD_CHANGELOG.md:3: Section 'Improved' maps to 'Improved'",
+        ))
+        .stderr(predicates::str::contains(
+            "OLD_CHANGELOG.md:5: Not a list item",
+        ));
+
+    assert!(!temp_dir.path().join(".changelogs").join("0.1.0").exists());
+}
+
+#[test]
+fn import_command_reports_sections_without_type_field() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+    let config_path = temp_dir.path().join("changelog.toml");
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    let type_field =
+        "[header_fields.type]\ntype = [ \"Bugfix\", \"Feature\", \"Misc\" ]\nrequired = false\n";
+    assert!(config.contains(type_field), "{config}");
+    std::fs::write(&config_path, config.replace(type_field, "")).unwrap();
+    std::fs::write(temp_dir.path().join("OLD_CHANGELOG.md"), CHANGELOG).unwrap();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["import", "OLD_CHANGELOG.md"])
+        .current_dir(&temp_dir)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "OLD_CHANGELOG.md:9: Entry is in section 'Added', but 'type' is not a header field",
+        ));
+
+    assert!(!temp_dir.path().join(".changelogs").join("0.2.0").exists());
+}
+
+#[test]
+fn import_command_refuses_existing_release() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+    std::fs::create_dir(temp_dir.path().join(".changelogs").join("0.1.0")).unwrap();
+    std::fs::write(temp_dir.path().join("OLD_CHANGELOG.md"), CHANGELOG).unwrap();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["import", "OLD_CHANGELOG.md"])
+        .current_dir(&temp_dir)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "Release directory exists already",
+        ));
+
+    assert!(!temp_dir.path().join(".changelogs").join("0.2.0").exists());
+}
+
+#[test]
+fn import_command_migrates_towncrier_fragments() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    std::fs::write(
+        temp_dir.path().join("towncrier.toml"),
+        r#"
+[tool.towncrier]
+directory = "changes"
+
+[[tool.towncrier.type]]
+directory = "feature"
+name = "Features"
+showcontent = true
+
+[[tool.towncrier.type]]
+directory = "bugfix"
+name = "Bugfixes"
+showcontent = true
+"#,
+    )
+    .unwrap();
+    let changes = temp_dir.path().join("changes");
+    std::fs::create_dir(&changes).unwrap();
+    std::fs::write(changes.join(".gitignore"), "!.gitignore\n").unwrap();
+    std::fs::write(
+        changes.join("123.bugfix.md"),
+        "Fix a crash\n\nMore details\n",
+    )
+    .unwrap();
+    std::fs::write(changes.join("+orphan.feature"), "A new feature\n").unwrap();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["import", "--from", "towncrier", "towncrier.toml"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Imported 2 fragments"));
+
+    // The types are mapped to the allowed values of the existing type field, which is kept
+    let config = std::fs::read_to_string(temp_dir.path().join("changelog.toml")).unwrap();
+    assert!(
+        config.contains("[header_fields.type]\ntype = [ \"Bugfix\", \"Feature\", \"Misc\" ]\nrequired = false\n"),
+        "{config}"
+    );
+    // Comments of the configuration are kept
+    assert!(config.contains("# Configuration for cargo-changelog"));
+
+    let unreleased = std::fs::read_dir(temp_dir.path().join(".changelogs/unreleased"))
+        .unwrap()
+        .map(|de| de.unwrap().path())
+        .filter(|path| !path.ends_with(".gitkeep"))
+        .map(|path| std::fs::read_to_string(path).unwrap())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(unreleased.contains("issue = 123"), "{unreleased}");
+    assert!(
+        unreleased.contains(r#"subject = "Fix a crash""#),
+        "{unreleased}"
+    );
+    assert!(unreleased.contains("More details"), "{unreleased}");
+    assert!(unreleased.contains(r#"type = "Feature""#), "{unreleased}");
+    assert!(unreleased.contains(r#"type = "Bugfix""#), "{unreleased}");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["verify-metadata"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+}
+
+#[test]
+fn import_command_migrates_changie_fragments() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    std::fs::write(
+        temp_dir.path().join(".changie.yaml"),
+        r#"
+changesDir: .changes
+unreleasedDir: unreleased
+components:
+  - cli
+  - lib
+kinds:
+  - label: Added
+  - label: Fixed
+custom:
+  - key: Issue
+    type: int
+    optional: true
+  - key: Author
+    type: string
+"#,
+    )
+    .unwrap();
+    let unreleased = temp_dir.path().join(".changes").join("unreleased");
+    std::fs::create_dir_all(&unreleased).unwrap();
+    std::fs::write(
+        unreleased.join("Added-20261018-120000.yaml"),
+        r#"component: cli
+kind: Added
+body: A new flag
+time: 2026-10-18T12:00:00.000000+02:00
+custom:
+  Issue: "42"
+  Author: alice
+"#,
+    )
+    .unwrap();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["import", "--from", "changie", ".changie.yaml"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Imported 1 fragments"));
+
+    let config = std::fs::read_to_string(temp_dir.path().join("changelog.toml")).unwrap();
+    assert!(
+        config.contains(r#"component = { type = ["cli", "lib"], required = true }"#),
+        "{config}"
+    );
+    assert!(
+        config.contains(r#"author = { type = "string", required = true }"#),
+        "{config}"
+    );
+
+    let fragment = std::fs::read_dir(temp_dir.path().join(".changelogs/unreleased"))
+        .unwrap()
+        .map(|de| de.unwrap().path())
+        .find(|path| !path.ends_with(".gitkeep"))
+        .map(|path| std::fs::read_to_string(path).unwrap())
+        .unwrap();
+    assert!(fragment.contains("issue = 42"), "{fragment}");
+    assert!(fragment.contains(r#"author = "alice""#), "{fragment}");
+    assert!(fragment.contains(r#"type = "Feature""#), "{fragment}");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["verify-metadata"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+}
+
+#[test]
+fn import_command_keeps_existing_header_fields() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    std::fs::write(
+        temp_dir.path().join("towncrier.toml"),
+        "[tool.towncrier]\ndirectory = \"changes\"\n",
+    )
+    .unwrap();
+    let changes = temp_dir.path().join("changes");
+    std::fs::create_dir(&changes).unwrap();
+    std::fs::write(changes.join("123.feature.md"), "A new feature\n").unwrap();
+
+    let config_before = std::fs::read_to_string(temp_dir.path().join("changelog.toml")).unwrap();
+    let output = assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["import", "--from", "towncrier", "towncrier.toml"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    assert!(!output.contains("Updated header fields"), "{output}");
+    let config_after = std::fs::read_to_string(temp_dir.path().join("changelog.toml")).unwrap();
+    assert_eq!(config_before, config_after);
+
+    // The optional type field of the default configuration stays optional
+    std::fs::write(
+        temp_dir
+            .path()
+            .join(".changelogs")
+            .join("unreleased")
+            .join("without-type.md"),
+        "+++\nsubject = \"Without a type\"\n+++\n",
+    )
+    .unwrap();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["verify-metadata"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+}
+
+#[test]
+fn import_command_imports_no_fragments_if_any_is_unmapped() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    std::fs::write(
+        temp_dir.path().join("towncrier.toml"),
+        "[tool.towncrier]\ndirectory = \"newsfragments\"\n",
+    )
+    .unwrap();
+    let newsfragments = temp_dir.path().join("newsfragments");
+    std::fs::create_dir(&newsfragments).unwrap();
+    std::fs::write(newsfragments.join("12.bugfix.md"), "Fix a crash\n").unwrap();
+    std::fs::write(newsfragments.join("abc.feature.md"), "A new feature\n").unwrap();
+    let config_before = std::fs::read_to_string(temp_dir.path().join("changelog.toml")).unwrap();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["import", "--from", "towncrier", "towncrier.toml"])
+        .current_dir(&temp_dir)
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("Nothing was imported"))
+        .stderr(predicates::str::contains("Issue 'abc' is not a number"));
+
+    let unreleased = std::fs::read_dir(temp_dir.path().join(".changelogs/unreleased"))
+        .unwrap()
+        .map(|de| de.unwrap().path())
+        .filter(|path| !path.ends_with(".gitkeep"))
+        .collect::<Vec<_>>();
+    assert!(unreleased.is_empty(), "{unreleased:?}");
+    let config_after = std::fs::read_to_string(temp_dir.path().join("changelog.toml")).unwrap();
+    assert_eq!(config_before, config_after);
+}
@@ -0,0 +1,88 @@
+mod common;
+
+fn files_in_dir(path: &std::path::Path) -> Vec<std::fs::DirEntry> {
+    std::fs::read_dir(path)
+        .unwrap_or_else(|e| panic!("Should exist: {} -> {e}", path.display()))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+#[test]
+fn revert_release_command_moves_fragments_back() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(
+        temp_dir.path(),
+        "revert_release_command_moves_fragments_back",
+    );
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args(["--set", "subject=Some change", "--set", "type=Misc"])
+        .assert()
+        .success();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "minor"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    let unreleased_dir = temp_dir.path().join(".changelogs").join("unreleased");
+    let release_dir = temp_dir.path().join(".changelogs").join("0.2.0");
+    assert_eq!(files_in_dir(&unreleased_dir).len(), 1);
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["revert-release", "0.2.0"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    assert_eq!(
+        files_in_dir(&unreleased_dir).len(),
+        2,
+        "Expected gitkeep and fragment in unreleased directory"
+    );
+    if release_dir.exists() {
+        panic!("Release directory should have been removed");
+    }
+}
+
+#[test]
+fn revert_release_command_refuses_tagged_release() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(temp_dir.path(), "revert_release_command_refuses_tagged");
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "minor"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    self::common::git_commit_all(temp_dir.path(), "Release 0.2.0");
+    self::common::git(temp_dir.path(), &["tag", "v0.2.0"]);
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["revert-release", "0.2.0"])
+        .current_dir(&temp_dir)
+        .assert()
+        .failure();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["revert-release", "--force", "0.2.0"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    if temp_dir.path().join(".changelogs").join("0.2.0").exists() {
+        panic!("Release directory should have been removed");
+    }
+}
@@ -44,3 +44,198 @@ fn verify_metadata_command_succeeds_with_empty_changelog() {
         .assert()
         .success();
 }
+
+#[test]
+fn verify_metadata_command_reports_invalid_header() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    let fragment_path = temp_dir
+        .path()
+        .join(".changelogs")
+        .join("unreleased")
+        .join("invalid.md");
+    std::fs::write(
+        &fragment_path,
+        indoc::indoc!(
+            r#"
+            +++
+            issue = "abc"
+            type = "Unknown"
+            foo = true
+            +++
+            text
+        "#
+        ),
+    )
+    .unwrap();
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["verify-metadata"])
+        .current_dir(&temp_dir)
+        .env("NO_COLOR", "1")
+        .assert()
+        .failure()
+        .get_output()
+        .clone();
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    for expected in [
+        "Required field 'subject' is missing",
+        "Field 'issue' has the wrong type",
+        "Value 'Unknown' is not allowed for field 'type'",
+        "Unknown field 'foo'",
+    ] {
+        assert!(
+            stderr.contains(expected),
+            "Expected '{expected}' in output:\n{stderr}"
+        );
+    }
+}
+
+#[test]
+fn verify_metadata_command_ignores_non_fragment_files() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(temp_dir.path(), "verify_metadata_ignores_non_fragments");
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args(["--set", "subject=Some change", "--set", "type=Misc"])
+        .assert()
+        .success();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "minor"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    std::fs::write(
+        temp_dir.path().join(".changelogs").join("suffix.md"),
+        "Some suffix",
+    )
+    .unwrap();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["verify-metadata"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+}
+
+fn write_invalid_fragment(temp_dir: &std::path::Path) {
+    std::fs::write(
+        temp_dir
+            .join(".changelogs")
+            .join("unreleased")
+            .join("invalid.md"),
+        "+++\nsubject = \"foo\"\nissue = \"abc\"\n+++\ntext\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn verify_metadata_command_reports_json() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+    write_invalid_fragment(temp_dir.path());
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["verify-metadata", "--format", "json"])
+        .current_dir(&temp_dir)
+        .assert()
+        .failure()
+        .get_output()
+        .clone();
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        report["checked"],
+        serde_json::json!([".changelogs/unreleased/invalid.md"])
+    );
+    assert_eq!(
+        report["errors"],
+        serde_json::json!([{
+            "path": ".changelogs/unreleased/invalid.md",
+            "line": 3,
+            "column": 9,
+            "field": "issue",
+            "rule": "type",
+            "message": "Field 'issue' has the wrong type: expected int, got string",
+        }])
+    );
+}
+
+#[test]
+fn verify_metadata_command_reports_sarif() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+    write_invalid_fragment(temp_dir.path());
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["verify-metadata", "--format", "sarif"])
+        .current_dir(&temp_dir)
+        .assert()
+        .failure()
+        .get_output()
+        .clone();
+
+    let log: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(log["version"], "2.1.0");
+    let result = &log["runs"][0]["results"][0];
+    assert_eq!(result["ruleId"], "type");
+    assert_eq!(
+        result["locations"][0]["physicalLocation"],
+        serde_json::json!({
+            "artifactLocation": { "uri": ".changelogs/unreleased/invalid.md" },
+            "region": { "startLine": 3, "startColumn": 9 },
+        })
+    );
+}
+
+#[test]
+fn verify_metadata_command_reports_junit() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+    write_invalid_fragment(temp_dir.path());
+
+    let output = assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["verify-metadata", "--format", "junit"])
+        .current_dir(&temp_dir)
+        .assert()
+        .failure()
+        .get_output()
+        .clone();
+
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        report.contains(
+            r#"<testcase name=".changelogs/unreleased/invalid.md" classname="verify-metadata">"#
+        ),
+        "Unexpected report: {report}"
+    );
+    assert!(
+        report.contains(r#"<failure type="type" message="Field &apos;issue&apos; has the wrong type: expected int, got string">"#),
+        "Unexpected report: {report}"
+    );
+}
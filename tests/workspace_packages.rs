@@ -0,0 +1,82 @@
+mod common;
+
+#[test]
+fn independently_versioned_packages_get_own_changelogs() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_workspace(temp_dir.path(), &[("foo", "0.1.0"), ("bar", "1.0.0")]);
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    for package in ["foo", "bar"] {
+        self::common::cargo_changelog_add(temp_dir.path())
+            .args([
+                "--package",
+                package,
+                "--set",
+                &format!("subject=Change in {package}"),
+                "--set",
+                "type=Misc",
+            ])
+            .assert()
+            .success();
+    }
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "--package", "foo", "minor"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "--package", "bar", "patch"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    let changelogs_dir = temp_dir.path().join(".changelogs");
+    if !changelogs_dir.join("foo").join("0.2.0").exists() {
+        panic!("Release dir 'foo/0.2.0' does not exist");
+    }
+    if !changelogs_dir.join("bar").join("1.0.1").exists() {
+        panic!("Release dir 'bar/1.0.1' does not exist");
+    }
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["generate", "--allow-dirty"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    let foo_changelog =
+        std::fs::read_to_string(temp_dir.path().join("foo").join("CHANGELOG.md")).unwrap();
+    assert!(foo_changelog.contains("## v0.2.0"), "{foo_changelog}");
+    assert!(foo_changelog.contains("Change in foo"), "{foo_changelog}");
+    assert!(!foo_changelog.contains("Change in bar"), "{foo_changelog}");
+
+    let bar_changelog =
+        std::fs::read_to_string(temp_dir.path().join("bar").join("CHANGELOG.md")).unwrap();
+    assert!(bar_changelog.contains("## v1.0.1"), "{bar_changelog}");
+    assert!(bar_changelog.contains("Change in bar"), "{bar_changelog}");
+
+    let root_changelog = std::fs::read_to_string(temp_dir.path().join("CHANGELOG.md")).unwrap();
+    assert!(!root_changelog.contains("Change in"), "{root_changelog}");
+}
+
+#[test]
+fn add_command_rejects_unknown_package() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_workspace(temp_dir.path(), &[("foo", "0.1.0")]);
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args(["--package", "baz", "--set", "subject=Some change"])
+        .assert()
+        .failure();
+}
@@ -0,0 +1,70 @@
+mod common;
+
+#[test]
+fn yank_command_marks_release_as_yanked() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo(temp_dir.path(), "yank_command_marks_release_as_yanked");
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    self::common::cargo_changelog_add(temp_dir.path())
+        .args(["--set", "subject=Some change", "--set", "type=Misc"])
+        .assert()
+        .success();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["create-release", "--codename", "Hedgehog", "minor"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["yank", "0.2.0"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+
+    let metadata_path = temp_dir
+        .path()
+        .join(".changelogs")
+        .join("0.2.0")
+        .join("metadata.toml");
+    let metadata = std::fs::read_to_string(&metadata_path).unwrap();
+    assert!(metadata.contains("yanked = true"), "{metadata}");
+    assert!(metadata.contains(r#"codename = "Hedgehog""#), "{metadata}");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["generate", "--allow-dirty"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+    let changelog = std::fs::read_to_string(temp_dir.path().join("CHANGELOG.md")).unwrap();
+    assert!(changelog.contains("[YANKED]"), "{changelog}");
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["yank", "--undo", "0.2.0"])
+        .current_dir(&temp_dir)
+        .assert()
+        .success();
+    let metadata = std::fs::read_to_string(&metadata_path).unwrap();
+    assert!(metadata.contains("yanked = false"), "{metadata}");
+}
+
+#[test]
+fn yank_command_fails_for_missing_release() {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("cargo-changelog")
+        .tempdir()
+        .unwrap();
+    self::common::init_git(temp_dir.path());
+    self::common::init_cargo_changelog(temp_dir.path());
+
+    assert_cmd::cargo::cargo_bin_cmd!("cargo-changelog")
+        .args(["yank", "1.0.0"])
+        .current_dir(&temp_dir)
+        .assert()
+        .failure();
+}